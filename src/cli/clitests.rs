@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::cli::*;
+use crate::swaybar::EscapePolicy;
+use std::ffi::OsString;
+
+#[cfg(test)]
+fn parse(args: &[&str]) -> Result<Options, UsageError> {
+    parse_args(args.iter().map(OsString::from))
+}
+
+#[test]
+fn check_default_options() {
+    assert_eq!(parse(&[]), Ok(Options::default()));
+}
+
+#[test]
+fn check_options() {
+    let options = parse(&[
+        "--config",
+        "/tmp/some config",
+        "--timerslack=50us",
+        "--protocol",
+        "i3bar",
+        "--check-config",
+        "--once",
+    ])
+    .unwrap();
+
+    assert_eq!(options.config, Some("/tmp/some config".into()));
+    assert_eq!(options.timerslack, 50_000);
+    assert_eq!(options.protocol, Protocol::Swaybar);
+    assert!(options.check_config);
+    assert!(options.once);
+    assert!(!options.version);
+
+    assert_eq!(
+        parse(&["--timerslack", "7.5ms"]).unwrap().timerslack,
+        7_500_000
+    );
+    assert_eq!(parse(&["--timerslack=1000"]).unwrap().timerslack, 1_000);
+    assert_eq!(
+        parse(&["--escape", "ascii"]).unwrap().escape,
+        EscapePolicy::AsciiOnly()
+    );
+    assert_eq!(
+        parse(&["--escape=conservative"]).unwrap().escape,
+        EscapePolicy::Conservative()
+    );
+}
+
+#[test]
+fn check_bad_options() {
+    assert!(parse(&["--bogus"]).is_err());
+    assert!(parse(&["--escape", "everything"]).is_err());
+    assert!(parse(&["--config"]).is_err());
+    assert!(parse(&["--check-config=yes"]).is_err());
+    assert!(parse(&["--once=yes"]).is_err());
+    assert!(parse(&["--protocol", "morse"]).is_err());
+    assert!(parse(&["--timerslack", "soon"]).is_err());
+    assert!(parse(&["--timerslack", "-5ms"]).is_err());
+    assert!(parse(&["--protocol", "waybar"]).is_err());
+    assert!(parse(&["--block", "clock"]).is_err());
+}
+
+#[test]
+fn check_waybar_options() {
+    let options = parse(&["--protocol=waybar", "--block=battery"]).unwrap();
+
+    assert_eq!(options.protocol, Protocol::Waybar);
+    assert_eq!(options.block.as_deref(), Some("battery"));
+}
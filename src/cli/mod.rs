@@ -0,0 +1,258 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::error::Error;
+use std::ffi::OsString;
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::swaybar::EscapePolicy;
+
+pub const USAGE: &str = "\
+Usage: ssstatus-rs [OPTIONS]
+
+Options:
+  --config <PATH>       Read the config from PATH
+  --timerslack <SLACK>  Timer slack, in ns, or with a us or ms suffix
+                        [default: 7.5ms]
+  --protocol <NAME>     Output protocol: swaybar, i3bar, text, tmux,
+                        waybar, lemonbar, or dzen2 [default: swaybar]
+  --block <NAME>        Which block to show, for waybar
+  --escape <POLICY>     How to escape JSON strings: minimal, ascii, or
+                        conservative [default: minimal]
+  --once                Print a single status line and exit
+  --check-config        Check the config file and exit
+  --version             Print the version and exit
+  --help                Print this help and exit
+";
+
+/**
+ * We couldn't make sense of the command line.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UsageError(pub String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for UsageError {}
+
+/**
+ * How to talk to the bar.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Protocol {
+    /**
+     * The i3bar JSON protocol, which swaybar speaks too.
+     */
+    #[default]
+    Swaybar,
+    /**
+     * Plain lines of text.
+     */
+    Text,
+    /**
+     * Lines of text with tmux style tags.
+     */
+    Tmux,
+    /**
+     * A waybar custom module, showing just one block.
+     */
+    Waybar,
+    /**
+     * Lines with lemonbar formatting tags.
+     */
+    Lemonbar,
+    /**
+     * Lines with dzen2 formatting commands.
+     */
+    Dzen2,
+}
+
+impl Protocol {
+    fn parse(name: &str) -> Result<Self, UsageError> {
+        match name {
+            "swaybar" | "i3bar" => Ok(Self::Swaybar),
+            "text" => Ok(Self::Text),
+            "tmux" => Ok(Self::Tmux),
+            "waybar" => Ok(Self::Waybar),
+            "lemonbar" => Ok(Self::Lemonbar),
+            "dzen2" => Ok(Self::Dzen2),
+            _ => Err(UsageError(format!("unknown protocol \"{}\"", name))),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Options {
+    pub config: Option<PathBuf>,
+    /**
+     * In nanoseconds.
+     */
+    pub timerslack: u64,
+    pub protocol: Protocol,
+    /**
+     * The block to show, for protocols that only show one.
+     */
+    pub block: Option<String>,
+    /**
+     * How to escape strings, for protocols that use JSON.
+     */
+    pub escape: EscapePolicy,
+    pub once: bool,
+    pub check_config: bool,
+    pub version: bool,
+    pub help: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            config: None,
+            // A vague guess at a decent slack.
+            timerslack: 7_500_000,
+            protocol: Protocol::default(),
+            block: None,
+            escape: EscapePolicy::MinimalEscaping(),
+            once: false,
+            check_config: false,
+            version: false,
+            help: false,
+        }
+    }
+}
+
+fn parse_escape_policy(name: &str) -> Result<EscapePolicy, UsageError> {
+    match name {
+        "minimal" => Ok(EscapePolicy::MinimalEscaping()),
+        "ascii" => Ok(EscapePolicy::AsciiOnly()),
+        "conservative" => Ok(EscapePolicy::Conservative()),
+        _ => Err(UsageError(format!("unknown escape policy \"{}\"", name))),
+    }
+}
+
+/**
+ * Parse a timer slack like `7500000`, `50us`, or `7.5ms` into
+ * nanoseconds.
+ */
+fn parse_timerslack(slack: &str) -> Result<u64, UsageError> {
+    let (number, scale) = if let Some(number) = slack.strip_suffix("ms") {
+        (number, 1_000_000.0)
+    } else if let Some(number) = slack.strip_suffix("us") {
+        (number, 1_000.0)
+    } else {
+        (slack.strip_suffix("ns").unwrap_or(slack), 1.0)
+    };
+
+    match number.parse::<f64>() {
+        Ok(number) if number.is_finite() && number >= 0.0 => Ok((number * scale).round() as u64),
+        _ => Err(UsageError(format!("bad timer slack \"{}\"", slack))),
+    }
+}
+
+/**
+ * Get the value for an option, either from after the '=' or from the
+ * next argument.
+ */
+fn option_value(
+    name: &str,
+    inline_value: Option<OsString>,
+    args: &mut impl Iterator<Item = OsString>,
+) -> Result<OsString, UsageError> {
+    inline_value
+        .or_else(|| args.next())
+        .ok_or_else(|| UsageError(format!("{} needs a value", name)))
+}
+
+fn option_string(
+    name: &str,
+    inline_value: Option<OsString>,
+    args: &mut impl Iterator<Item = OsString>,
+) -> Result<String, UsageError> {
+    option_value(name, inline_value, args)?
+        .into_string()
+        .map_err(|value| UsageError(format!("bad value {:?} for {}", value, name)))
+}
+
+/**
+ * Parse our arguments, not including the program name.
+ */
+pub fn parse_args(args: impl IntoIterator<Item = OsString>) -> Result<Options, UsageError> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let arg = arg
+            .into_string()
+            .map_err(|arg| UsageError(format!("bad argument {:?}", arg)))?;
+
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(OsString::from(value))),
+            None => (arg.as_str(), None),
+        };
+
+        let flag = match name {
+            "--config" => {
+                options.config = Some(option_value(name, inline_value, &mut args)?.into());
+                continue;
+            }
+            "--timerslack" => {
+                options.timerslack =
+                    parse_timerslack(&option_string(name, inline_value, &mut args)?)?;
+                continue;
+            }
+            "--protocol" => {
+                options.protocol = Protocol::parse(&option_string(name, inline_value, &mut args)?)?;
+                continue;
+            }
+            "--block" => {
+                options.block = Some(option_string(name, inline_value, &mut args)?);
+                continue;
+            }
+            "--escape" => {
+                options.escape =
+                    parse_escape_policy(&option_string(name, inline_value, &mut args)?)?;
+                continue;
+            }
+            "--once" => &mut options.once,
+            "--check-config" => &mut options.check_config,
+            "--version" => &mut options.version,
+            "-h" | "--help" => &mut options.help,
+            _ => return Err(UsageError(format!("unknown option \"{}\"", name))),
+        };
+
+        if inline_value.is_some() {
+            return Err(UsageError(format!("{} doesn't take a value", name)));
+        }
+
+        *flag = true;
+    }
+
+    match (options.protocol, &options.block) {
+        (Protocol::Waybar, None) => Err(UsageError("waybar needs --block".to_string())),
+        (Protocol::Waybar, Some(_)) | (_, None) => Ok(options),
+        (_, Some(_)) => Err(UsageError("--block only works with waybar".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod clitests;
@@ -0,0 +1,139 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::config::*;
+use crate::data::battery::BatteryWarningLevel;
+use crate::swaybar::{Align, MinWidth};
+use crate::time::tick::TickGranularity;
+
+#[test]
+fn check_empty_config() {
+    assert!(parse("").unwrap().blocks.is_empty());
+    assert!(
+        parse("# just a comment\n\n; and another\n")
+            .unwrap()
+            .blocks
+            .is_empty()
+    );
+}
+
+#[test]
+fn check_blocks_in_order() {
+    let config = parse(
+        "[block clock]\n\
+         format = %a %H:%M\n\
+         min_width = 120px\n\
+         align = center\n\
+         \n\
+         [block battery]\n\
+         short_format = \" %p%%\"\n\
+         color = #00FF00\n\
+         urgent_at = action\n",
+    )
+    .unwrap();
+
+    assert_eq!(config.blocks.len(), 2);
+
+    let clock = &config.blocks[0];
+    assert!(matches!(clock.kind, BlockKind::Clock));
+    assert_eq!(clock.format, "%a %H:%M");
+    assert_eq!(clock.short_format, "%H:%M");
+    assert_eq!(clock.min_width, Some(MinWidth::Pixels(120)));
+    assert_eq!(clock.align, Some(Align::Center));
+
+    let battery = &config.blocks[1];
+    assert_eq!(battery.short_format, " %p%%");
+    assert_eq!(battery.color.as_deref(), Some("#00FF00"));
+    assert_eq!(
+        battery.min_width,
+        Some(MinWidth::Text("000%+ 00:00".to_string()))
+    );
+    match &battery.kind {
+        BlockKind::Battery(style) => {
+            assert_eq!(style.urgent_at, BatteryWarningLevel::Action);
+            assert_eq!(style.warn_at, BatteryWarningLevel::Low);
+        }
+        _ => panic!("expected a battery block"),
+    }
+}
+
+#[test]
+fn check_config_errors() {
+    let line_of = |text| parse(text).unwrap_err().line;
+
+    assert_eq!(line_of("format = %H:%M\n"), 1);
+    assert_eq!(line_of("[block clock]\nformat = %Q\n"), 2);
+    assert_eq!(line_of("[block battery]\nformat = %H\n"), 2);
+    assert_eq!(line_of("[block network]\nformat = %q\n"), 2);
+    assert_eq!(line_of("[block clock]\n\nwarn_at = low\n"), 3);
+    assert_eq!(line_of("[block clock]\ncolor = red\n"), 2);
+    assert_eq!(line_of("[block weather]\n"), 1);
+    assert_eq!(line_of("[blocks clock]\n"), 1);
+    assert_eq!(line_of("[block clock\n"), 1);
+    assert_eq!(line_of("[block clock]\njust some words\n"), 2);
+}
+
+#[test]
+fn check_network_block() {
+    let config = parse("[block network]\nformat = %n (%k)\n").unwrap();
+
+    let network = &config.blocks[0];
+    assert!(matches!(network.kind, BlockKind::Network));
+    assert_eq!(network.format, "%n (%k)");
+    assert_eq!(network.short_format, "%k%b %c");
+    assert_eq!(network.min_width, None);
+}
+
+#[test]
+fn check_shows_network() {
+    assert!(!Config::default().shows_network());
+    assert!(
+        !parse("[block battery]\n[block clock]\n")
+            .unwrap()
+            .shows_network()
+    );
+    assert!(
+        parse("[block clock]\n[block network]\n")
+            .unwrap()
+            .shows_network()
+    );
+}
+
+#[test]
+fn check_tick_granularity() {
+    let granularity = |text| parse(text).unwrap().tick_granularity();
+
+    assert_eq!(
+        Config::default().tick_granularity(),
+        TickGranularity::Minute
+    );
+    assert_eq!(granularity("[block battery]\n"), TickGranularity::Day);
+    assert_eq!(
+        granularity("[block clock]\nformat = %F\nshort_format = %a\n"),
+        TickGranularity::Day
+    );
+    assert_eq!(
+        granularity("[block clock]\nformat = %F\n[block clock]\nformat = %T\n"),
+        TickGranularity::Second
+    );
+    assert_eq!(
+        granularity("[block clock]\nformat = %F\nshort_format = %I %p\n"),
+        TickGranularity::Hour
+    );
+}
@@ -0,0 +1,390 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use crate::data::battery::{self, BatteryWarningLevel, BatteryWarningStyle};
+use crate::data::network;
+use crate::swaybar::{Align, Markup, MinWidth};
+use crate::time::{self, tick::TickGranularity};
+
+/**
+ * Something is wrong with the config file.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigError {
+    /**
+     * The line we noticed the problem on, counting from 1.
+     */
+    pub line: usize,
+    pub what: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "config line {}: {}", self.line, self.what)
+    }
+}
+
+impl Error for ConfigError {}
+
+/**
+ * What a block shows, and anything specific to that.
+ */
+#[derive(Clone, Debug)]
+pub enum BlockKind {
+    Battery(BatteryWarningStyle),
+    Clock,
+    Network,
+}
+
+impl BlockKind {
+    /**
+     * The name the bar knows the block by, e.g. in click events.
+     */
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Battery(_) => "battery",
+            Self::Clock => "clock",
+            Self::Network => "network",
+        }
+    }
+}
+
+/**
+ * How to lay out one block.
+ */
+#[derive(Clone, Debug)]
+pub struct BlockConfig {
+    pub kind: BlockKind,
+    pub format: String,
+    pub short_format: String,
+    pub color: Option<String>,
+    pub background: Option<String>,
+    pub border: Option<String>,
+    pub min_width: Option<MinWidth>,
+    pub align: Option<Align>,
+    pub separator: Option<bool>,
+    pub separator_block_width: Option<u32>,
+    /**
+     * With Pango markup, the formats can have markup in them, and
+     * everything we fill in gets escaped.
+     */
+    pub markup: Option<Markup>,
+}
+
+impl BlockConfig {
+    pub fn battery() -> Self {
+        BlockConfig {
+            kind: BlockKind::Battery(BatteryWarningStyle::default()),
+            format: battery::DEFAULT_FORMAT.to_string(),
+            short_format: battery::DEFAULT_SHORT_FORMAT.to_string(),
+            color: None,
+            background: None,
+            border: None,
+            min_width: Some(MinWidth::Text("000%+ 00:00".to_string())),
+            align: None,
+            separator: None,
+            separator_block_width: None,
+            markup: None,
+        }
+    }
+
+    pub fn clock() -> Self {
+        BlockConfig {
+            kind: BlockKind::Clock,
+            format: time::DEFAULT_FORMAT.to_string(),
+            short_format: time::DEFAULT_SHORT_FORMAT.to_string(),
+            min_width: Some(MinWidth::Text("00:00".to_string())),
+            ..Self::battery()
+        }
+    }
+
+    pub fn network() -> Self {
+        BlockConfig {
+            kind: BlockKind::Network,
+            format: network::DEFAULT_FORMAT.to_string(),
+            short_format: network::DEFAULT_SHORT_FORMAT.to_string(),
+            min_width: None,
+            ..Self::battery()
+        }
+    }
+
+    fn check_format(&self, format: &str) -> Result<(), String> {
+        match self.kind {
+            BlockKind::Battery(_) => battery::check_format(format),
+            BlockKind::Clock => time::check_format(format),
+            BlockKind::Network => network::check_format(format),
+        }
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match (key, &mut self.kind) {
+            ("format", _) => {
+                self.check_format(value)?;
+                self.format = value.to_string();
+            }
+            ("short_format", _) => {
+                self.check_format(value)?;
+                self.short_format = value.to_string();
+            }
+            ("color", _) => self.color = Some(parse_color(value)?),
+            ("background", _) => self.background = Some(parse_color(value)?),
+            ("border", _) => self.border = Some(parse_color(value)?),
+            ("min_width", _) => self.min_width = Some(parse_min_width(value)),
+            ("align", _) => self.align = Some(parse_align(value)?),
+            ("separator", _) => self.separator = Some(parse_bool(value)?),
+            ("separator_block_width", _) => self.separator_block_width = Some(parse_number(value)?),
+            ("markup", _) => self.markup = Some(parse_markup(value)?),
+            ("warn_at", BlockKind::Battery(style)) => style.warn_at = parse_level(value)?,
+            ("warn_color", BlockKind::Battery(style)) => style.warn_color = parse_color(value)?,
+            ("critical_at", BlockKind::Battery(style)) => style.critical_at = parse_level(value)?,
+            ("critical_color", BlockKind::Battery(style)) => {
+                style.critical_color = parse_color(value)?
+            }
+            ("urgent_at", BlockKind::Battery(style)) => style.urgent_at = parse_level(value)?,
+            _ => {
+                return Err(format!(
+                    "unknown key \"{}\" for {} block",
+                    key,
+                    self.kind.name()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/**
+ * Which blocks to show, in order, and how.
+ */
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub blocks: Vec<BlockConfig>,
+}
+
+impl Config {
+    /**
+     * Whether there's a network block, and so a reason to keep an eye
+     * on NetworkManager.
+     */
+    pub fn shows_network(&self) -> bool {
+        self.blocks
+            .iter()
+            .any(|block| matches!(block.kind, BlockKind::Network))
+    }
+
+    /**
+     * How often the clock blocks need to change. Without any, there's
+     * still the odd thing that goes by date, so tick once a day.
+     */
+    pub fn tick_granularity(&self) -> TickGranularity {
+        self.blocks
+            .iter()
+            .filter(|block| matches!(block.kind, BlockKind::Clock))
+            .flat_map(|block| [&block.format, &block.short_format])
+            .map(|format| TickGranularity::for_format(format))
+            .min()
+            .unwrap_or(TickGranularity::Day)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            blocks: vec![BlockConfig::battery(), BlockConfig::clock()],
+        }
+    }
+}
+
+fn parse_color(value: &str) -> Result<String, String> {
+    match value.strip_prefix('#') {
+        Some(hex) if [6, 8].contains(&hex.len()) && hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+            Ok(value.to_string())
+        }
+        _ => Err(format!("bad color \"{}\", expected #RRGGBB[AA]", value)),
+    }
+}
+
+fn parse_min_width(value: &str) -> MinWidth {
+    match value.strip_suffix("px").map(str::parse::<u32>) {
+        Some(Ok(pixels)) => MinWidth::Pixels(pixels),
+        _ => MinWidth::Text(value.to_string()),
+    }
+}
+
+fn parse_align(value: &str) -> Result<Align, String> {
+    match value {
+        "left" => Ok(Align::Left),
+        "center" => Ok(Align::Center),
+        "right" => Ok(Align::Right),
+        _ => Err(format!("bad alignment \"{}\"", value)),
+    }
+}
+
+fn parse_markup(value: &str) -> Result<Markup, String> {
+    match value {
+        "none" => Ok(Markup::None),
+        "pango" => Ok(Markup::Pango),
+        _ => Err(format!("bad markup \"{}\"", value)),
+    }
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value {
+        "true" | "yes" => Ok(true),
+        "false" | "no" => Ok(false),
+        _ => Err(format!("bad boolean \"{}\"", value)),
+    }
+}
+
+fn parse_number(value: &str) -> Result<u32, String> {
+    value
+        .parse::<u32>()
+        .map_err(|_| format!("bad number \"{}\"", value))
+}
+
+fn parse_level(value: &str) -> Result<BatteryWarningLevel, String> {
+    match value {
+        "none" => Ok(BatteryWarningLevel::None),
+        "discharging" => Ok(BatteryWarningLevel::Discharging),
+        "low" => Ok(BatteryWarningLevel::Low),
+        "critical" => Ok(BatteryWarningLevel::Critical),
+        "action" => Ok(BatteryWarningLevel::Action),
+        _ => Err(format!("bad warning level \"{}\"", value)),
+    }
+}
+
+/**
+ * Values can be quoted, to keep whitespace at either end.
+ */
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/**
+ * Parse a config file. It's INI-like: each `[block <kind>]` section adds
+ * a block, in order, and the `key = value` lines after it set up that
+ * block. Blank lines and lines starting with '#' or ';' are ignored.
+ */
+pub fn parse(text: &str) -> Result<Config, ConfigError> {
+    let mut blocks: Vec<BlockConfig> = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let error = |what: String| ConfigError { line: i + 1, what };
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(section) = line.strip_prefix('[') {
+            let section = section
+                .strip_suffix(']')
+                .ok_or_else(|| error("expected ']'".to_string()))?;
+
+            let block = match section.split_whitespace().collect::<Vec<_>>()[..] {
+                ["block", "battery"] => BlockConfig::battery(),
+                ["block", "clock"] => BlockConfig::clock(),
+                ["block", "network"] => BlockConfig::network(),
+                ["block", kind] => return Err(error(format!("unknown block \"{}\"", kind))),
+                _ => return Err(error(format!("unknown section \"{}\"", section))),
+            };
+
+            blocks.push(block);
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| error("expected key = value".to_string()))?;
+
+        blocks
+            .last_mut()
+            .ok_or_else(|| error("setting outside of a block".to_string()))?
+            .set(key.trim(), unquote(value.trim()))
+            .map_err(error)?;
+    }
+
+    Ok(Config { blocks })
+}
+
+/**
+ * Where the config file lives, unless we're told otherwise.
+ */
+pub fn default_path() -> Option<PathBuf> {
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(config_home.join("ssstatus-rs").join("config"))
+}
+
+/**
+ * Where to read the config from.
+ */
+#[derive(Clone, Debug)]
+pub struct ConfigLocation {
+    pub path: PathBuf,
+    /**
+     * Whether we can fall back to the built-in layout if the file
+     * isn't there. Only the default location is optional.
+     */
+    pub optional: bool,
+}
+
+impl ConfigLocation {
+    /**
+     * Use the given path, or else the default one, if we can figure
+     * out where that is.
+     */
+    pub fn find(path: Option<PathBuf>) -> Option<Self> {
+        match path {
+            Some(path) => Some(ConfigLocation {
+                path,
+                optional: false,
+            }),
+            None => default_path().map(|path| ConfigLocation {
+                path,
+                optional: true,
+            }),
+        }
+    }
+
+    pub fn load(&self) -> Result<Config, Box<dyn Error + Send + Sync>> {
+        match fs::read_to_string(&self.path) {
+            Ok(text) => Ok(parse(&text)?),
+            Err(e) if e.kind() == ErrorKind::NotFound && self.optional => Ok(Config::default()),
+            Err(e) => Err(format!("{}: {}", self.path.display(), e).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod configtests;
@@ -18,20 +18,269 @@
  */
 
 use std::fmt;
+use std::time::Duration;
 
-#[derive(Clone, Copy)]
+use crate::data::{EscapeData, no_escaping};
+
+/**
+ * What the battery is up to, as UPower reports it in the `State`
+ * property.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BatteryState {
+    #[default]
+    Unknown,
+    Charging,
+    Discharging,
+    Empty,
+    FullyCharged,
+    PendingCharge,
+    PendingDischarge,
+}
+
+impl From<u32> for BatteryState {
+    fn from(state: u32) -> Self {
+        match state {
+            1 => Self::Charging,
+            2 => Self::Discharging,
+            3 => Self::Empty,
+            4 => Self::FullyCharged,
+            5 => Self::PendingCharge,
+            6 => Self::PendingDischarge,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl BatteryState {
+    /**
+     * A single character to show whether we're plugged in or not.
+     */
+    fn indicator(&self) -> char {
+        match self {
+            Self::Unknown => ' ',
+            Self::Charging => '+',
+            Self::Discharging | Self::Empty => '-',
+            Self::FullyCharged => '=',
+            Self::PendingCharge | Self::PendingDischarge => '~',
+        }
+    }
+
+    /**
+     * A name for the state, for bars that style things by class.
+     */
+    pub fn class(&self) -> &'static str {
+        match self {
+            Self::Unknown => "unknown",
+            Self::Charging => "charging",
+            Self::Discharging => "discharging",
+            Self::Empty => "empty",
+            Self::FullyCharged => "full",
+            Self::PendingCharge | Self::PendingDischarge => "pending",
+        }
+    }
+}
+
+/**
+ * How worried UPower thinks we should be about the battery, from the
+ * `WarningLevel` property. These are in order of increasing concern.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BatteryWarningLevel {
+    #[default]
+    Unknown,
+    None,
+    Discharging,
+    Low,
+    Critical,
+    Action,
+}
+
+impl From<u32> for BatteryWarningLevel {
+    fn from(level: u32) -> Self {
+        match level {
+            1 => Self::None,
+            2 => Self::Discharging,
+            3 => Self::Low,
+            4 => Self::Critical,
+            5 => Self::Action,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
 pub struct BatteryStatus {
     pub percentage: f64,
+    pub state: BatteryState,
+    pub warning_level: BatteryWarningLevel,
+    /**
+     * UPower's estimates, in seconds. Zero means it doesn't know.
+     */
+    pub time_to_empty: i64,
+    pub time_to_full: i64,
+}
+
+impl BatteryStatus {
+    /**
+     * How long until the battery is empty or full, whichever one it's
+     * heading towards, if UPower has an estimate.
+     */
+    pub fn time_remaining(&self) -> Option<Duration> {
+        let secs = match self.state {
+            BatteryState::Charging => self.time_to_full,
+            BatteryState::Discharging => self.time_to_empty,
+            _ => 0,
+        };
+
+        u64::try_from(secs)
+            .ok()
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+}
+
+/**
+ * The formats we use if the config doesn't say otherwise. `%p` is the
+ * percentage, `%s` is the charging indicator, `%t` is the time
+ * remaining as H:MM (or nothing, if there's no estimate), and `%%` is a
+ * literal '%'.
+ */
+pub const DEFAULT_FORMAT: &str = "%p%%%s %t";
+pub const DEFAULT_SHORT_FORMAT: &str = "%p%%%s";
+
+/**
+ * Check that a battery format only uses placeholders we know about.
+ */
+pub fn check_format(format: &str) -> Result<(), String> {
+    let mut chars = format.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            match chars.next() {
+                Some('p' | 's' | 't' | '%') => {}
+                Some(other) => return Err(format!("unknown battery placeholder %{}", other)),
+                None => return Err("battery format ends with a lone %".to_string()),
+            }
+        }
+    }
+
+    Ok(())
 }
 
 impl fmt::Display for BatteryStatus {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:3.0}%", self.percentage)
+        write!(
+            f,
+            "{}",
+            FormattedBatteryStatus(*self, DEFAULT_FORMAT, no_escaping)
+        )
+    }
+}
+
+/**
+ * The battery status, laid out according to a format, with the values
+ * we fill in escaped. Trailing whitespace is dropped, so a missing time
+ * estimate doesn't leave a gap.
+ */
+pub struct FormattedBatteryStatus<'a>(pub BatteryStatus, pub &'a str, pub EscapeData);
+
+impl fmt::Display for FormattedBatteryStatus<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let status = &self.0;
+        let escape = self.2;
+        let mut out = String::new();
+        let mut chars = self.1.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                out.push(c);
+                continue;
+            }
+
+            match chars.next() {
+                Some('p') => out.push_str(&escape(&format!("{:3.0}", status.percentage))),
+                Some('s') => out.push_str(&escape(&status.state.indicator().to_string())),
+                Some('t') => {
+                    if let Some(remaining) = status.time_remaining() {
+                        let minutes = remaining.as_secs() / 60;
+                        out.push_str(&escape(&format!("{}:{:02}", minutes / 60, minutes % 60)));
+                    }
+                }
+                Some('%') => out.push('%'),
+                Some(other) => {
+                    out.push('%');
+                    out.push(other);
+                }
+                None => out.push('%'),
+            }
+        }
+
+        write!(f, "{}", out.trim_end())
+    }
+}
+
+/**
+ * How to draw attention to the battery when UPower warns about it.
+ */
+#[derive(Clone, Debug)]
+pub struct BatteryWarningStyle {
+    /**
+     * From this warning level on, use `warn_color`.
+     */
+    pub warn_at: BatteryWarningLevel,
+    pub warn_color: String,
+    /**
+     * From this warning level on, use `critical_color` instead.
+     */
+    pub critical_at: BatteryWarningLevel,
+    pub critical_color: String,
+    /**
+     * From this warning level on, mark the battery as urgent.
+     */
+    pub urgent_at: BatteryWarningLevel,
+}
+
+impl Default for BatteryWarningStyle {
+    fn default() -> Self {
+        BatteryWarningStyle {
+            warn_at: BatteryWarningLevel::Low,
+            warn_color: String::from("#FFFF00"),
+            critical_at: BatteryWarningLevel::Critical,
+            critical_color: String::from("#FF0000"),
+            urgent_at: BatteryWarningLevel::Critical,
+        }
     }
 }
 
-impl From<f64> for BatteryStatus {
-    fn from(percentage: f64) -> Self {
-        BatteryStatus { percentage }
+impl BatteryWarningStyle {
+    /**
+     * The color to use for the battery, if it shouldn't be the default.
+     */
+    pub fn color(&self, status: &BatteryStatus) -> Option<&str> {
+        if status.warning_level >= self.critical_at {
+            Some(&self.critical_color)
+        } else if status.warning_level >= self.warn_at {
+            Some(&self.warn_color)
+        } else {
+            None
+        }
+    }
+
+    /**
+     * The same as `color`, but for bars that style things by class.
+     */
+    pub fn class(&self, status: &BatteryStatus) -> Option<&'static str> {
+        if status.warning_level >= self.critical_at {
+            Some("critical")
+        } else if status.warning_level >= self.warn_at {
+            Some("warning")
+        } else {
+            None
+        }
+    }
+
+    pub fn urgent(&self, status: &BatteryStatus) -> bool {
+        status.warning_level >= self.urgent_at
     }
 }
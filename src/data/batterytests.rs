@@ -0,0 +1,103 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::data::battery::*;
+use crate::data::no_escaping;
+use crate::swaybar::escape_pango;
+
+#[cfg(test)]
+fn discharging(percentage: f64, time_to_empty: i64) -> BatteryStatus {
+    BatteryStatus {
+        percentage,
+        state: BatteryState::Discharging,
+        time_to_empty,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+fn formatted(status: BatteryStatus, format: &str) -> String {
+    FormattedBatteryStatus(status, format, no_escaping).to_string()
+}
+
+#[test]
+fn check_battery_placeholders() {
+    let status = discharging(42.4, 2 * 3600 + 5 * 60 + 59);
+
+    assert_eq!(formatted(status, "%p"), " 42");
+    assert_eq!(formatted(status, "%s"), "-");
+    assert_eq!(formatted(status, "%t"), "2:05");
+    assert_eq!(formatted(status, "%%"), "%");
+    assert_eq!(formatted(status, DEFAULT_FORMAT), " 42%- 2:05");
+    assert_eq!(formatted(status, DEFAULT_SHORT_FORMAT), " 42%-");
+    assert_eq!(
+        formatted(status, "bat %p%% (%t left)"),
+        "bat  42% (2:05 left)"
+    );
+
+    let charging = BatteryStatus {
+        percentage: 100.0,
+        state: BatteryState::Charging,
+        time_to_full: 90,
+        ..Default::default()
+    };
+    assert_eq!(formatted(charging, DEFAULT_FORMAT), "100%+ 0:01");
+}
+
+#[test]
+fn check_battery_without_estimate() {
+    // UPower says zero when it doesn't know.
+    let status = discharging(7.0, 0);
+
+    assert_eq!(formatted(status, "%t"), "");
+    assert_eq!(formatted(status, "[%t]"), "[]");
+
+    // Only the time left is for the direction we're heading.
+    let full = BatteryStatus {
+        state: BatteryState::FullyCharged,
+        time_to_empty: 3600,
+        ..status
+    };
+    assert_eq!(formatted(full, "%t"), "");
+}
+
+#[test]
+fn check_battery_drops_trailing_whitespace() {
+    let status = discharging(55.0, 0);
+
+    assert_eq!(formatted(status, DEFAULT_FORMAT), " 55%-");
+    assert_eq!(formatted(status, "%p %t \t "), " 55");
+
+    // An unknown state has a blank indicator, which goes too.
+    let unknown = BatteryStatus {
+        state: BatteryState::Unknown,
+        ..status
+    };
+    assert_eq!(formatted(unknown, "%p%%%s"), " 55%");
+}
+
+#[test]
+fn check_battery_keeps_format_markup() {
+    let status = discharging(80.0, 0);
+
+    assert_eq!(
+        FormattedBatteryStatus(status, "<b>%p%%</b>%s", escape_pango).to_string(),
+        "<b> 80%</b>-"
+    );
+}
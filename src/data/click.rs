@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+/**
+ * A click on one of our blocks, as reported by the bar.
+ */
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClickEvent {
+    /**
+     * The name of the block that was clicked, if it had one.
+     */
+    pub name: Option<String>,
+    /**
+     * The instance of the block that was clicked, if it had one.
+     */
+    pub instance: Option<String>,
+    /**
+     * The X11 button number, e.g. 1 for left click.
+     */
+    pub button: u32,
+    /**
+     * Where the click happened, in absolute coordinates.
+     */
+    pub x: i64,
+    pub y: i64,
+    /**
+     * Which modifier keys were held down, e.g. `Shift` or `Mod4`.
+     */
+    pub modifiers: Vec<String>,
+}
+
+pub const BUTTON_LEFT: u32 = 1;
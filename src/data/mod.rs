@@ -19,15 +19,21 @@
 
 use chrono::Utc;
 use chrono_tz::Tz;
+use std::borrow::Cow;
 use std::error::Error;
 use std::fmt;
 use std::time::Instant;
 
+use crate::config::Config;
 use crate::time::DateTimeData;
 
 pub mod battery;
+pub mod click;
+pub mod network;
 
 use battery::BatteryStatus;
+use click::ClickEvent;
+use network::NetworkStatus;
 
 pub struct MaybeData<T>(pub Result<Option<(Instant, T)>, Box<dyn Error + Send + Sync>>);
 
@@ -43,9 +49,39 @@ impl<T: fmt::Display> fmt::Display for MaybeData<T> {
     }
 }
 
+/**
+ * How to escape text that came from our data sources, e.g. so that an
+ * error message can't break markup. Text from the config is left
+ * alone, since that's where the markup comes from.
+ */
+pub type EscapeData = fn(&str) -> Cow<'_, str>;
+
+pub fn no_escaping(text: &str) -> Cow<'_, str> {
+    Cow::Borrowed(text)
+}
+
+/**
+ * One of our data sources isn't around right now, e.g. because the
+ * service we get it from isn't running.
+ */
+#[derive(Debug)]
+pub struct SourceUnavailable(pub &'static str);
+
+impl fmt::Display for SourceUnavailable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no {}", self.0)
+    }
+}
+
+impl Error for SourceUnavailable {}
+
 pub struct StatusbarData {
     battery: MaybeData<BatteryStatus>,
+    network: MaybeData<NetworkStatus>,
     timezone: MaybeData<Tz>,
+    timezone_stale: bool,
+    short_clock: bool,
+    config_problem: Option<Box<dyn Error + Send + Sync>>,
 }
 
 impl StatusbarData {
@@ -54,7 +90,16 @@ impl StatusbarData {
             Ok(Some((timestamp, level))) => {
                 MaybeData::<BatteryStatus>(Ok(Some((*timestamp, *level))))
             }
-            _ => MaybeData::<BatteryStatus>(Ok(None)),
+            Ok(None) => MaybeData::<BatteryStatus>(Ok(None)),
+            Err(e) => MaybeData::<BatteryStatus>(Err(e.to_string().into())),
+        }
+    }
+
+    pub fn network(&self) -> MaybeData<NetworkStatus> {
+        match &self.network.0 {
+            Ok(Some((timestamp, status))) => MaybeData(Ok(Some((*timestamp, status.clone())))),
+            Ok(None) => MaybeData(Ok(None)),
+            Err(e) => MaybeData(Err(e.to_string().into())),
         }
     }
 
@@ -71,10 +116,37 @@ impl StatusbarData {
     pub fn new() -> StatusbarData {
         StatusbarData {
             battery: MaybeData(Ok(None)),
+            network: MaybeData(Ok(None)),
             timezone: MaybeData(Ok(None)),
+            timezone_stale: false,
+            short_clock: false,
+            config_problem: None,
         }
     }
 
+    /**
+     * Whether the clock should be shown in its shortened form.
+     */
+    pub fn short_clock(&self) -> bool {
+        self.short_clock
+    }
+
+    pub fn toggle_short_clock(&mut self) {
+        self.short_clock = !self.short_clock;
+    }
+
+    /**
+     * Why we couldn't reload the config, if we couldn't. We keep using
+     * the last good one in the meantime.
+     */
+    pub fn config_problem(&self) -> Option<&(dyn Error + Send + Sync)> {
+        self.config_problem.as_deref()
+    }
+
+    pub fn set_config_problem(&mut self, problem: Option<Box<dyn Error + Send + Sync>>) {
+        self.config_problem = problem;
+    }
+
     pub fn update_battery_maybedata(&mut self, bat: MaybeData<BatteryStatus>) {
         self.battery = bat;
     }
@@ -93,8 +165,45 @@ impl StatusbarData {
         self.battery = MaybeData(Ok(Some((Instant::now(), bat))))
     }
 
+    pub fn update_network_maybedata(&mut self, network: MaybeData<NetworkStatus>) {
+        self.network = network;
+    }
+
+    pub fn update_network_result(
+        &mut self,
+        network: Result<NetworkStatus, Box<dyn Error + Send + Sync>>,
+    ) {
+        self.network = MaybeData(network.map(|status| Some((Instant::now(), status))));
+    }
+
     pub fn update_timezone_maybedata(&mut self, tz: MaybeData<Tz>) {
         self.timezone = tz;
+        self.timezone_stale = false;
+    }
+
+    /**
+     * Whether we've lost track of the timezone. We keep showing the
+     * last one we knew about, since it's probably still right.
+     */
+    pub fn timezone_stale(&self) -> bool {
+        self.timezone_stale
+    }
+
+    /**
+     * We lost the system bus, so we can't trust the battery or network
+     * status anymore, and if we got the timezone from there, it might
+     * change without us noticing.
+     */
+    pub fn mark_system_bus_lost(
+        &mut self,
+        problem: Box<dyn Error + Send + Sync>,
+        timezone_too: bool,
+    ) {
+        self.network = MaybeData(Err(problem.to_string().into()));
+        self.battery = MaybeData(Err(problem));
+        if timezone_too {
+            self.timezone_stale = true;
+        }
     }
 
     pub fn update_timezone_result(&mut self, tz: Result<Tz, Box<dyn Error + Send + Sync>>) {
@@ -105,7 +214,7 @@ impl StatusbarData {
     }
 
     pub fn update_timezone(&mut self, tz: Tz) {
-        self.timezone = MaybeData(Ok(Some((Instant::now(), tz))))
+        self.update_timezone_maybedata(MaybeData(Ok(Some((Instant::now(), tz)))));
     }
 }
 
@@ -116,8 +225,8 @@ impl fmt::Display for StatusbarData {
 }
 
 pub enum StatusbarChangeCause {
-    // We're at the next minute.
-    NextMinute,
+    // We're at the next tick of the clock.
+    NextTick,
 
     // The timezone changed.
     TzChange(MaybeData<Tz>),
@@ -127,4 +236,30 @@ pub enum StatusbarChangeCause {
 
     // The battery status changed.
     BatteryChange(MaybeData<BatteryStatus>),
+
+    // The network status changed.
+    NetworkChange(MaybeData<NetworkStatus>),
+
+    // The user clicked on one of our blocks.
+    Click(ClickEvent),
+
+    // We lost the system bus, and everything we got from it.
+    SystemBusLost {
+        problem: Box<dyn Error + Send + Sync>,
+        timezone_too: bool,
+    },
+
+    // The config file changed, and this is what's in it now.
+    ConfigReload(Result<Config, Box<dyn Error + Send + Sync>>),
+
+    // The bar is hidden, so we should hold off on output.
+    Stop,
+
+    // The bar is visible again, so we should resume output.
+    Cont,
 }
+
+#[cfg(test)]
+mod batterytests;
+#[cfg(test)]
+mod networktests;
@@ -0,0 +1,266 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::fmt;
+
+use crate::data::{EscapeData, no_escaping};
+
+/**
+ * What sort of connection we're using, from the active connection's
+ * `Type` property.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConnectionKind {
+    /**
+     * There's no primary connection at all.
+     */
+    #[default]
+    Disconnected,
+    Wired,
+    Wifi,
+    Vpn,
+    Other,
+}
+
+impl From<&str> for ConnectionKind {
+    fn from(kind: &str) -> Self {
+        match kind {
+            "" => Self::Disconnected,
+            "802-3-ethernet" => Self::Wired,
+            "802-11-wireless" => Self::Wifi,
+            "vpn" | "wireguard" => Self::Vpn,
+            _ => Self::Other,
+        }
+    }
+}
+
+impl ConnectionKind {
+    /**
+     * A short name, for `%k`.
+     */
+    fn abbreviation(&self) -> &'static str {
+        match self {
+            Self::Disconnected => "down",
+            Self::Wired => "eth",
+            Self::Wifi => "wifi",
+            Self::Vpn => "vpn",
+            Self::Other => "net",
+        }
+    }
+
+    /**
+     * A name for the kind, for bars that style things by class.
+     */
+    pub fn class(&self) -> &'static str {
+        match self {
+            Self::Disconnected => "disconnected",
+            Self::Wired => "wired",
+            Self::Wifi => "wifi",
+            Self::Vpn => "vpn",
+            Self::Other => "other",
+        }
+    }
+}
+
+/**
+ * How far NetworkManager thinks we can get, from the `Connectivity`
+ * property.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Connectivity {
+    #[default]
+    Unknown,
+    None,
+    Portal,
+    Limited,
+    Full,
+}
+
+impl From<u32> for Connectivity {
+    fn from(connectivity: u32) -> Self {
+        match connectivity {
+            1 => Self::None,
+            2 => Self::Portal,
+            3 => Self::Limited,
+            4 => Self::Full,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl Connectivity {
+    /**
+     * What to say about it, if anything. Full connectivity is what we
+     * expect, and NetworkManager might not be checking at all.
+     */
+    pub fn problem(&self) -> Option<&'static str> {
+        match self {
+            Self::Unknown | Self::Full => None,
+            Self::None => Some("offline"),
+            Self::Portal => Some("portal"),
+            Self::Limited => Some("limited"),
+        }
+    }
+}
+
+/**
+ * The Wi-Fi access point we're connected to.
+ */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccessPoint {
+    pub ssid: String,
+    /**
+     * The signal quality, in percent.
+     */
+    pub strength: u8,
+}
+
+impl AccessPoint {
+    /**
+     * The signal strength as a single bar, from '▁' to '█'.
+     */
+    fn bar(&self) -> char {
+        const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+        BARS[(usize::from(self.strength.min(100)) * (BARS.len() - 1) + 50) / 100]
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NetworkStatus {
+    pub kind: ConnectionKind,
+    /**
+     * The name of the primary connection, which is usually the SSID
+     * for Wi-Fi.
+     */
+    pub name: String,
+    pub connectivity: Connectivity,
+    /**
+     * Which access point we're using, if the primary connection is
+     * Wi-Fi and we know.
+     */
+    pub access_point: Option<AccessPoint>,
+}
+
+impl NetworkStatus {
+    /**
+     * What to call the connection. For Wi-Fi, the SSID is what people
+     * know it by, even if the connection got named something else.
+     */
+    fn display_name(&self) -> &str {
+        match &self.access_point {
+            Some(access_point) if !access_point.ssid.is_empty() => &access_point.ssid,
+            _ => &self.name,
+        }
+    }
+}
+
+/**
+ * The formats we use if the config doesn't say otherwise. `%k` is the
+ * kind of connection, `%n` is its name (the SSID, for Wi-Fi), `%c` is
+ * what's wrong with the connectivity (or nothing, if it's fine), `%p`
+ * is the Wi-Fi signal strength in percent, `%b` is the signal strength
+ * as a bar, and `%%` is a literal '%'. Without Wi-Fi, `%p` and `%b` are
+ * empty.
+ */
+pub const DEFAULT_FORMAT: &str = "%k %n %c";
+pub const DEFAULT_SHORT_FORMAT: &str = "%k%b %c";
+
+/**
+ * Check that a network format only uses placeholders we know about.
+ */
+pub fn check_format(format: &str) -> Result<(), String> {
+    let mut chars = format.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            match chars.next() {
+                Some('k' | 'n' | 'c' | 'p' | 'b' | '%') => {}
+                Some(other) => return Err(format!("unknown network placeholder %{}", other)),
+                None => return Err("network format ends with a lone %".to_string()),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl fmt::Display for NetworkStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            FormattedNetworkStatus(self, DEFAULT_FORMAT, no_escaping)
+        )
+    }
+}
+
+/**
+ * The network status, laid out according to a format, with the values
+ * we fill in escaped. Like for the battery, trailing whitespace is
+ * dropped.
+ */
+pub struct FormattedNetworkStatus<'a>(pub &'a NetworkStatus, pub &'a str, pub EscapeData);
+
+impl fmt::Display for FormattedNetworkStatus<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let status = self.0;
+        let escape = self.2;
+        let mut out = String::new();
+        let mut chars = self.1.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                out.push(c);
+                continue;
+            }
+
+            match chars.next() {
+                Some('k') => out.push_str(&escape(status.kind.abbreviation())),
+                Some('n') => out.push_str(&escape(status.display_name())),
+                Some('c') => {
+                    // Being down already says we're offline.
+                    if status.kind != ConnectionKind::Disconnected
+                        && let Some(problem) = status.connectivity.problem()
+                    {
+                        out.push_str(&escape(problem));
+                    }
+                }
+                Some('p') => {
+                    if let Some(access_point) = &status.access_point {
+                        out.push_str(&escape(&access_point.strength.to_string()));
+                    }
+                }
+                Some('b') => {
+                    if let Some(access_point) = &status.access_point {
+                        out.push_str(&escape(&access_point.bar().to_string()));
+                    }
+                }
+                Some('%') => out.push('%'),
+                Some(other) => {
+                    out.push('%');
+                    out.push(other);
+                }
+                None => out.push('%'),
+            }
+        }
+
+        write!(f, "{}", out.trim_end())
+    }
+}
@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::data::network::*;
+use crate::data::no_escaping;
+use crate::swaybar::escape_pango;
+
+#[cfg(test)]
+fn wifi(name: &str, ssid: &str, strength: u8) -> NetworkStatus {
+    NetworkStatus {
+        kind: ConnectionKind::Wifi,
+        name: name.to_string(),
+        connectivity: Connectivity::Full,
+        access_point: Some(AccessPoint {
+            ssid: ssid.to_string(),
+            strength,
+        }),
+    }
+}
+
+#[cfg(test)]
+fn formatted(status: &NetworkStatus, format: &str) -> String {
+    FormattedNetworkStatus(status, format, no_escaping).to_string()
+}
+
+#[test]
+fn check_signal_bar() {
+    assert_eq!(formatted(&wifi("home", "home", 0), "%b"), "▁");
+    assert_eq!(formatted(&wifi("home", "home", 50), "%b"), "▅");
+    assert_eq!(formatted(&wifi("home", "home", 100), "%b"), "█");
+    assert_eq!(formatted(&wifi("home", "home", 70), "%p%% %b"), "70% ▆");
+    assert_eq!(
+        formatted(&wifi("home", "home", 70), DEFAULT_SHORT_FORMAT),
+        "wifi▆"
+    );
+}
+
+#[test]
+fn check_no_signal_without_wifi() {
+    let wired = NetworkStatus {
+        kind: ConnectionKind::Wired,
+        name: "Wired connection 1".to_string(),
+        connectivity: Connectivity::Full,
+        access_point: None,
+    };
+
+    assert_eq!(formatted(&wired, "[%p%b]"), "[]");
+    assert_eq!(formatted(&wired, DEFAULT_FORMAT), "eth Wired connection 1");
+    assert_eq!(formatted(&wired, DEFAULT_SHORT_FORMAT), "eth");
+}
+
+#[test]
+fn check_ssid_over_connection_name() {
+    assert_eq!(formatted(&wifi("home 2", "Home", 80), "%n"), "Home");
+
+    // Hidden networks don't always tell us their SSID.
+    assert_eq!(formatted(&wifi("home 2", "", 80), "%n"), "home 2");
+}
+
+#[test]
+fn check_connectivity_problems() {
+    let portal = NetworkStatus {
+        connectivity: Connectivity::Portal,
+        ..wifi("cafe", "cafe", 40)
+    };
+    assert_eq!(formatted(&portal, DEFAULT_FORMAT), "wifi cafe portal");
+
+    // Being down already says we're offline.
+    let down = NetworkStatus {
+        connectivity: Connectivity::None,
+        ..Default::default()
+    };
+    assert_eq!(formatted(&down, "%k %c"), "down");
+    assert_eq!(formatted(&down, "%k (%c)"), "down ()");
+}
+
+#[test]
+fn check_network_literal_percent() {
+    assert_eq!(formatted(&wifi("home", "home", 5), "%p%%"), "5%");
+    assert_eq!(formatted(&wifi("home", "home", 5), "100%% %k"), "100% wifi");
+}
+
+#[test]
+fn check_network_escapes_ssid() {
+    assert_eq!(
+        FormattedNetworkStatus(&wifi("x", "<&>", 50), "<i>%n</i>", escape_pango).to_string(),
+        "<i>&lt;&amp;&gt;</i>"
+    );
+}
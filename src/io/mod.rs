@@ -19,6 +19,8 @@
 
 use tokio::io::{AsyncWrite, Stderr, stderr};
 
+pub mod watch;
+
 pub struct StatusbarIOContext<'a> {
     pub statusbar_output: Box<dyn AsyncWrite + Unpin + Send + Sync + 'a>,
     pub debug_output: Stderr,
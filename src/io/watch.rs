@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use nix::sys::inotify::{AddWatchFlags, InitFlags, Inotify};
+use std::error::Error;
+use std::ffi::OsString;
+use std::fs::File;
+use std::os::fd::AsFd;
+use std::path::Path;
+use tokio::io::Interest;
+use tokio::io::unix::AsyncFd;
+
+/**
+ * Watch some files in a directory, and wake up when any of them are
+ * created, written, replaced, or removed. We watch the directory rather
+ * than the files themselves, since the usual way to change e.g. a
+ * symlink is to rename a new one over it.
+ */
+pub struct DirectoryWatch {
+    inotify: Inotify,
+    afd: AsyncFd<File>,
+    names: Vec<OsString>,
+}
+
+impl DirectoryWatch {
+    pub fn new(dir: &Path, names: &[String]) -> Result<Self, Box<dyn Error>> {
+        Self::with_flags(dir, names, AddWatchFlags::IN_CREATE)
+    }
+
+    /**
+     * Like `new`, but don't wake up when a file is created, only once
+     * it's been written out. Files that get written in place are still
+     * empty when they're created.
+     */
+    pub fn new_for_written(dir: &Path, names: &[String]) -> Result<Self, Box<dyn Error>> {
+        Self::with_flags(dir, names, AddWatchFlags::empty())
+    }
+
+    fn with_flags(
+        dir: &Path,
+        names: &[String],
+        extra_flags: AddWatchFlags,
+    ) -> Result<Self, Box<dyn Error>> {
+        let inotify = Inotify::init(InitFlags::IN_NONBLOCK | InitFlags::IN_CLOEXEC)?;
+
+        inotify.add_watch(
+            dir,
+            extra_flags
+                | AddWatchFlags::IN_CLOSE_WRITE
+                | AddWatchFlags::IN_MOVED_TO
+                | AddWatchFlags::IN_MOVED_FROM
+                | AddWatchFlags::IN_DELETE
+                | AddWatchFlags::IN_ONLYDIR,
+        )?;
+
+        let copy_inotify = File::from(inotify.as_fd().try_clone_to_owned()?);
+        let afd = AsyncFd::with_interest(copy_inotify, Interest::READABLE)?;
+
+        Ok(DirectoryWatch {
+            inotify,
+            afd,
+            names: names.iter().map(OsString::from).collect(),
+        })
+    }
+
+    /**
+     * Wait until one of the files we're watching changes.
+     */
+    pub async fn changed(&self) -> Result<(), Box<dyn Error>> {
+        loop {
+            let mut guard = self.afd.readable().await?;
+
+            let events = match self.inotify.read_events() {
+                Ok(events) => events,
+                Err(nix::errno::Errno::EAGAIN) => {
+                    guard.clear_ready();
+                    continue;
+                }
+                Err(eno) => return Err(eno.into()),
+            };
+
+            guard.retain_ready();
+
+            // If the queue overflowed, we don't know what we missed, so
+            // assume the worst.
+            if events.iter().any(|event| {
+                event.mask.contains(AddWatchFlags::IN_Q_OVERFLOW)
+                    || event
+                        .name
+                        .as_ref()
+                        .is_some_and(|name| self.names.contains(name))
+            }) {
+                return Ok(());
+            }
+        }
+    }
+}
@@ -18,35 +18,59 @@
  */
 
 use chrono_tz::Tz;
-use dbus::arg::RefArg;
-use dbus::message::{Message, SignalArgs};
+use dbus::arg::{PropMap, RefArg, Variant};
+use dbus::channel::MatchingReceiver;
+use dbus::channel::Token;
+use dbus::message::{MatchRule, Message, SignalArgs};
 use dbus::nonblock::stdintf::org_freedesktop_dbus::{
     Properties, PropertiesPropertiesChanged as PropChange,
 };
 use dbus::nonblock::{LocalConnection, MsgMatch, Proxy};
 use dbus::strings::{Interface, Member};
 use dbus_tokio::connection;
+use dbus_tokio::connection::IOResourceError;
 use std::convert::Infallible;
 use std::error::Error;
+use std::future::{Future, poll_fn};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::pin::{Pin, pin};
+use std::process::ExitCode;
 use std::rc::Rc;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::task::Poll;
 use std::time::{Duration, Instant};
-use tokio::io::{self as tokio_io, AsyncWrite};
+use tokio::io::{self as tokio_io, AsyncRead, AsyncWrite};
 use tokio::runtime::Builder;
 use tokio::sync::Mutex;
-use tokio::sync::mpsc::{Sender, channel};
-use tokio::task::spawn_local;
+use tokio::sync::mpsc::{
+    Sender, UnboundedReceiver, UnboundedSender, WeakUnboundedSender, channel, unbounded_channel,
+};
+use tokio::sync::watch::{
+    Receiver as WatchReceiver, Sender as WatchSender, channel as watch_channel,
+};
+use tokio::task::{JoinError, JoinHandle, LocalSet, spawn_local};
+use tokio::time::sleep;
 
+mod cli;
+mod config;
 mod data;
 mod io;
+mod render;
 mod swaybar;
 mod time;
 
-use crate::data::battery::BatteryStatus;
-use crate::data::{MaybeData, StatusbarChangeCause};
+use crate::config::{Config, ConfigLocation};
+use crate::data::battery::{BatteryState, BatteryStatus, BatteryWarningLevel};
+use crate::data::network::{AccessPoint, ConnectionKind, Connectivity, NetworkStatus};
+use crate::data::{MaybeData, SourceUnavailable, StatusbarChangeCause, StatusbarData};
 use crate::io::StatusbarIOContext;
-use crate::swaybar::run_statusbar_updater;
-use crate::time::{ClockTickCallbacks, tick_every_minute};
+use crate::io::watch::DirectoryWatch;
+use crate::render::{Renderer, renderer_for, run_statusbar_updater, status_blocks};
+use crate::swaybar::{StopContSignals, forward_stop_cont, read_click_events};
+use crate::time::tick::{TickGranularity, TickRequest};
+use crate::time::zone::LocalTimezone;
+use crate::time::{ClockTickCallbacks, tick_clock};
 
 async fn wrangle_lifetimes_update(
     change_q: Sender<StatusbarChangeCause>,
@@ -55,144 +79,1301 @@ async fn wrangle_lifetimes_update(
     Ok(change_q.send(data).await?)
 }
 
-async fn listen_to_upower(
-    sys_conn: Arc<LocalConnection>,
-    change_q: Sender<StatusbarChangeCause>,
-) -> Result<MsgMatch, Box<dyn Error>> {
-    let rule = PropChange::match_rule(
-        None,
-        Some(&"/org/freedesktop/UPower/devices/DisplayDevice".into()),
-    )
-    .static_clone();
+/**
+ * Update the battery status from a set of UPower Device properties,
+ * returning whether any of the ones we care about were in there.
+ */
+fn update_from_upower(status: &mut BatteryStatus, props: &PropMap) -> bool {
+    let mut changed = false;
+
+    if let Some(pct) = props.get("Percentage") {
+        status.percentage = pct
+            .as_f64()
+            .expect("Percentage is documented as \"double\"");
+        changed = true;
+    }
+
+    if let Some(state) = props.get("State") {
+        status.state = BatteryState::from(
+            u32::try_from(state.as_u64().expect("State is documented as \"uint32\""))
+                .expect("State is documented as \"uint32\""),
+        );
+        changed = true;
+    }
+
+    if let Some(level) = props.get("WarningLevel") {
+        status.warning_level = BatteryWarningLevel::from(
+            u32::try_from(
+                level
+                    .as_u64()
+                    .expect("WarningLevel is documented as \"uint32\""),
+            )
+            .expect("WarningLevel is documented as \"uint32\""),
+        );
+        changed = true;
+    }
+
+    if let Some(secs) = props.get("TimeToEmpty") {
+        status.time_to_empty = secs
+            .as_i64()
+            .expect("TimeToEmpty is documented as \"int64\"");
+        changed = true;
+    }
+
+    if let Some(secs) = props.get("TimeToFull") {
+        status.time_to_full = secs
+            .as_i64()
+            .expect("TimeToFull is documented as \"int64\"");
+        changed = true;
+    }
+
+    changed
+}
+
+/**
+ * Make D-Bus errors about a service not being around short enough to
+ * show in a block.
+ */
+fn describe_dbus_error(service: &'static str, e: dbus::Error) -> Box<dyn Error + Send + Sync> {
+    match e.name() {
+        Some(
+            "org.freedesktop.DBus.Error.ServiceUnknown"
+            | "org.freedesktop.DBus.Error.NameHasNoOwner",
+        ) => Box::new(SourceUnavailable(service)),
+        _ => Box::new(e),
+    }
+}
+
+/**
+ * Watch for a service appearing on and disappearing from the bus. The
+ * callback gets whether the name has an owner now.
+ */
+async fn watch_name_owner(
+    sys_conn: &Arc<LocalConnection>,
+    name: &'static str,
+    mut on_change: impl FnMut(bool) + 'static,
+) -> Result<Token, Box<dyn Error>> {
+    let rule = MatchRule::new_signal("org.freedesktop.DBus", "NameOwnerChanged")
+        .with_sender("org.freedesktop.DBus")
+        .static_clone();
+
+    // MatchRule doesn't know about argument matches, so we ask the bus
+    // to only send us changes for this name, and double check them.
+    sys_conn
+        .add_match_no_cb(&format!("{},arg0='{}'", rule.match_str(), name))
+        .await?;
+
+    Ok(sys_conn.start_receive(
+        rule,
+        Box::new(move |mesg: Message, _conn: &LocalConnection| {
+            if let Ok((changed_name, _old_owner, new_owner)) = mesg.read3::<&str, &str, &str>()
+                && changed_name == name
+            {
+                on_change(!new_owner.is_empty());
+            }
+
+            true
+        }),
+    ))
+}
+
+/**
+ * What keeps one of our data sources listening on the system bus.
+ */
+struct BusListener {
+    signal_match: MsgMatch,
+    /**
+     * Sources that we need to fetch from when they show up have a
+     * watch on their name, too.
+     */
+    owner_watch: Option<Token>,
+}
+
+impl BusListener {
+    fn stop(self, sys_conn: &LocalConnection) {
+        sys_conn.stop_receive(self.signal_match.token());
+        if let Some(owner_watch) = self.owner_watch {
+            sys_conn.stop_receive(owner_watch);
+        }
+    }
+}
+
+const UPOWER_DISPLAY_DEVICE: &str = "/org/freedesktop/UPower/devices/DisplayDevice";
+const TIMEDATE_PATH: &str = "/org/freedesktop/timedate1";
+const NETWORKMANAGER_PATH: &str = "/org/freedesktop/NetworkManager";
 
-    let upower_proxy = Proxy::new(
+fn upower_proxy(sys_conn: Arc<LocalConnection>) -> Proxy<'static, Arc<LocalConnection>> {
+    Proxy::new(
         "org.freedesktop.UPower",
-        rule.path.clone().unwrap(),
+        UPOWER_DISPLAY_DEVICE,
         Duration::from_secs(5),
-        sys_conn.clone(),
-    );
+        sys_conn,
+    )
+}
+
+/**
+ * Get everything we care about from UPower at once.
+ */
+async fn get_upower_status(
+    upower_proxy: &Proxy<'static, Arc<LocalConnection>>,
+) -> Result<BatteryStatus, Box<dyn Error + Send + Sync>> {
+    let props = upower_proxy
+        .get_all("org.freedesktop.UPower.Device")
+        .await
+        .map_err(|e| describe_dbus_error("UPower", e))?;
+
+    let mut status = BatteryStatus::default();
+    update_from_upower(&mut status, &props);
+
+    Ok(status)
+}
+
+fn timedate_proxy(sys_conn: Arc<LocalConnection>) -> Proxy<'static, Arc<LocalConnection>> {
+    Proxy::new(
+        "org.freedesktop.timedate1",
+        TIMEDATE_PATH,
+        Duration::from_secs(5),
+        sys_conn,
+    )
+}
 
-    let iface = match Interface::new("org.freedesktop.UPower.Device") {
+fn timedate_names() -> (Interface<'static>, Member<'static>) {
+    let iface = match Interface::new("org.freedesktop.timedate1") {
         Ok(interface) => interface,
         Err(_description) => {
             unreachable!("This hardcoded name is the correct one so it must be okay.")
         }
     };
 
-    let percent_member = match Member::new("Percentage") {
+    let tz_name_member = match Member::new("Timezone") {
         Ok(member) => member,
         Err(_description) => {
             unreachable!("This hardcoded name is the correct one so it must be okay.")
         }
     };
 
-    // TODO: go introspect and make sure that Percentage is marked emits-change.
+    (iface, tz_name_member)
+}
+
+/**
+ * What the UPower refetcher should go get.
+ */
+enum UPowerRefetch {
+    Everything,
+    Property(String),
+}
+
+/**
+ * A property we fetched by itself, laid out like the ones that come in
+ * PropertiesChanged. `Properties::get` already took it out of its
+ * variant, so it needs wrapping back up.
+ */
+fn refetched_props(prop: String, value: Box<dyn RefArg>) -> PropMap {
+    PropMap::from([(prop, Variant(value))])
+}
+
+/**
+ * Go fetch UPower properties when we don't know their values, e.g. if
+ * they got invalidated, since PropertiesChanged didn't tell us their
+ * new values. We can't do this from the match callback itself, since
+ * that would reenter dbus.
+ */
+async fn refetch_upower_props(
+    upower_proxy: Proxy<'static, Arc<LocalConnection>>,
+    battery: Arc<StdMutex<BatteryStatus>>,
+    mut refetch_q: UnboundedReceiver<UPowerRefetch>,
+    change_q: Sender<StatusbarChangeCause>,
+) -> Result<(), Box<dyn Error>> {
+    while let Some(what) = refetch_q.recv().await {
+        let fetched = match what {
+            UPowerRefetch::Everything => get_upower_status(&upower_proxy).await.map(|fresh| {
+                let mut status = battery
+                    .lock()
+                    .expect("Nothing should panic while holding this.");
+                *status = fresh;
+                *status
+            }),
+            UPowerRefetch::Property(prop) => upower_proxy
+                .get::<Box<dyn RefArg>>("org.freedesktop.UPower.Device", &prop)
+                .await
+                .map(|value| {
+                    let mut status = battery
+                        .lock()
+                        .expect("Nothing should panic while holding this.");
+                    update_from_upower(&mut status, &refetched_props(prop, value));
+                    *status
+                })
+                .map_err(|e| describe_dbus_error("UPower", e)),
+        };
+        let got_bat_when = Instant::now();
+
+        let update = MaybeData(fetched.map(|status| Some((got_bat_when, status))));
+
+        change_q
+            .send(StatusbarChangeCause::BatteryChange(update))
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn listen_to_upower(
+    sys_conn: Arc<LocalConnection>,
+    change_q: Sender<StatusbarChangeCause>,
+) -> Result<BusListener, Box<dyn Error>> {
+    let rule = PropChange::match_rule(None, Some(&UPOWER_DISPLAY_DEVICE.into())).static_clone();
+
+    let upower_proxy = upower_proxy(sys_conn.clone());
+
+    // TODO: go introspect and make sure that Percentage, State,
+    // WarningLevel, TimeToEmpty, and TimeToFull are marked emits-change.
+
+    // PropertiesChanged only tells us about what changed, so we need
+    // to hang onto the rest.
+    let battery = Arc::new(StdMutex::new(BatteryStatus::default()));
 
     let cloned_change_q = change_q.clone();
+    let cloned_battery = battery.clone();
+    let (refetch_tx, refetch_rx) = unbounded_channel();
+    let cloned_refetch_tx = refetch_tx.clone();
+
+    let prop_match =
+        sys_conn
+            .add_match(rule)
+            .await?
+            .cb(move |_mesg: Message, change: PropChange| {
+                if change.interface_name == "org.freedesktop.UPower.Device" {
+                    for prop in change.invalidated_properties {
+                        if [
+                            "Percentage",
+                            "State",
+                            "WarningLevel",
+                            "TimeToEmpty",
+                            "TimeToFull",
+                        ]
+                        .contains(&prop.as_str())
+                        {
+                            // If the refetcher is gone, it already reported why.
+                            let _ = cloned_refetch_tx.send(UPowerRefetch::Property(prop));
+                        }
+                    }
 
-    let mtch = sys_conn.add_match(rule).await?.cb(move |_mesg: Message, change: PropChange| {
-        if change.interface_name == "org.freedesktop.UPower.Device" {
-            let maybe_new_pct = {
-                if let Some(new_value) = change.changed_properties.get("Percentage") {
-                    Some(new_value.as_f64().expect("Percentage is documented as \"double\""))
-                } else if change.invalidated_properties.contains(&String::from("Percentage")) {
-                    unimplemented!("Firing off a message here would reenter dbus and I haven't thought that out yet.");
-                } else {
-                    None
+                    let mut status = cloned_battery
+                        .lock()
+                        .expect("Nothing should panic while holding this.");
+
+                    if update_from_upower(&mut status, &change.changed_properties) {
+                        let got_bat_when = Instant::now();
+                        spawn_local(wrangle_lifetimes_update(
+                            cloned_change_q.clone(),
+                            StatusbarChangeCause::BatteryChange(MaybeData(Ok(Some((
+                                got_bat_when,
+                                *status,
+                            ))))),
+                        ));
+                    }
                 }
-            };
 
-            if let Some(new_pct) = maybe_new_pct {
-                let got_bat_when = Instant::now();
-                spawn_local(wrangle_lifetimes_update(cloned_change_q.clone(), StatusbarChangeCause::BatteryChange(MaybeData(Ok(Some((got_bat_when, BatteryStatus::from(new_pct))))))));
-            }
+                true
+            });
+
+    // If UPower isn't running yet, start working once it is. If it goes
+    // away, whatever we knew about the battery is stale.
+    let cloned_change_q = change_q.clone();
+    let cloned_refetch_tx = refetch_tx.clone();
+
+    let owner_watch = watch_name_owner(&sys_conn, "org.freedesktop.UPower", move |has_owner| {
+        if has_owner {
+            let _ = cloned_refetch_tx.send(UPowerRefetch::Everything);
+        } else {
+            spawn_local(wrangle_lifetimes_update(
+                cloned_change_q.clone(),
+                StatusbarChangeCause::BatteryChange(MaybeData(Err(Box::new(SourceUnavailable(
+                    "UPower",
+                ))))),
+            ));
         }
+    })
+    .await?;
 
-        true
-    });
+    spawn_local(refetch_upower_props(
+        upower_proxy,
+        battery,
+        refetch_rx,
+        change_q,
+    ));
 
-    // Get the starting percentage.
-    let start_pct = upower_proxy.get::<f64>(&iface, &percent_member).await?;
-    let got_bat_when = Instant::now();
+    // Get the starting status.
+    let _ = refetch_tx.send(UPowerRefetch::Everything);
 
-    change_q
-        .send(StatusbarChangeCause::BatteryChange(MaybeData(Ok(Some((
-            got_bat_when,
-            BatteryStatus::from(start_pct),
-        ))))))
-        .await?;
+    Ok(BusListener {
+        signal_match: prop_match,
+        owner_watch: Some(owner_watch),
+    })
+}
 
-    Ok(mtch)
+fn networkmanager_proxy(
+    sys_conn: Arc<LocalConnection>,
+    path: String,
+) -> Proxy<'static, Arc<LocalConnection>> {
+    Proxy::new(
+        "org.freedesktop.NetworkManager",
+        path,
+        Duration::from_secs(5),
+        sys_conn,
+    )
 }
 
-async fn listen_for_tzchange(
+/**
+ * Get the primary connection and how connected we are, along with the
+ * Wi-Fi device the connection is on, if it's Wi-Fi. The access point
+ * is left for the caller to look up.
+ */
+async fn get_primary_connection(
+    sys_conn: &Arc<LocalConnection>,
+) -> Result<(NetworkStatus, Option<String>), Box<dyn Error + Send + Sync>> {
+    let props = networkmanager_proxy(sys_conn.clone(), NETWORKMANAGER_PATH.to_string())
+        .get_all("org.freedesktop.NetworkManager")
+        .await
+        .map_err(|e| describe_dbus_error("NetworkManager", e))?;
+
+    let mut status = NetworkStatus::default();
+
+    if let Some(connectivity) = props.get("Connectivity") {
+        status.connectivity = Connectivity::from(
+            u32::try_from(
+                connectivity
+                    .as_u64()
+                    .expect("Connectivity is documented as \"uint32\""),
+            )
+            .expect("Connectivity is documented as \"uint32\""),
+        );
+    }
+
+    // No primary connection shows up as the root path.
+    let primary = match props
+        .get("PrimaryConnection")
+        .and_then(|path| path.as_str())
+    {
+        Some(path) if path != "/" => path.to_string(),
+        _ => return Ok((status, None)),
+    };
+
+    let active = networkmanager_proxy(sys_conn.clone(), primary)
+        .get_all("org.freedesktop.NetworkManager.Connection.Active")
+        .await
+        .map_err(|e| describe_dbus_error("NetworkManager", e))?;
+
+    if let Some(kind) = active.get("Type").and_then(|kind| kind.as_str()) {
+        status.kind = ConnectionKind::from(kind);
+    }
+    if let Some(name) = active.get("Id").and_then(|name| name.as_str()) {
+        status.name = name.to_string();
+    }
+
+    let wifi_device = if status.kind == ConnectionKind::Wifi {
+        active
+            .get("Devices")
+            .and_then(|devices| devices.0.as_iter())
+            .and_then(|mut devices| devices.next())
+            .and_then(|device| device.as_str())
+            .map(str::to_string)
+    } else {
+        None
+    };
+
+    Ok((status, wifi_device))
+}
+
+/**
+ * Which access point a Wi-Fi device is using, if any.
+ */
+async fn get_active_access_point(
+    sys_conn: &Arc<LocalConnection>,
+    device: &str,
+) -> Result<Option<String>, Box<dyn Error + Send + Sync>> {
+    let path = networkmanager_proxy(sys_conn.clone(), device.to_string())
+        .get::<dbus::Path<'static>>(
+            "org.freedesktop.NetworkManager.Device.Wireless",
+            "ActiveAccessPoint",
+        )
+        .await
+        .map_err(|e| describe_dbus_error("NetworkManager", e))?;
+
+    // Like for the primary connection, none is the root path.
+    Ok((&*path != "/").then(|| path.to_string()))
+}
+
+/**
+ * Update an access point from a set of AccessPoint properties,
+ * returning whether any of the ones we care about were in there.
+ */
+fn update_from_access_point(access_point: &mut AccessPoint, props: &PropMap) -> bool {
+    let mut changed = false;
+
+    if let Some(ssid) = props.get("Ssid") {
+        let bytes = ssid
+            .0
+            .as_iter()
+            .expect("Ssid is documented as \"array of byte\"")
+            .map(|byte| {
+                u8::try_from(
+                    byte.as_u64()
+                        .expect("Ssid is documented as \"array of byte\""),
+                )
+                .expect("Ssid is documented as \"array of byte\"")
+            })
+            .collect::<Vec<_>>();
+
+        // SSIDs are just bytes, but they're almost always UTF-8.
+        access_point.ssid = String::from_utf8_lossy(&bytes).into_owned();
+        changed = true;
+    }
+
+    if let Some(strength) = props.get("Strength") {
+        access_point.strength = u8::try_from(
+            strength
+                .as_u64()
+                .expect("Strength is documented as \"byte\""),
+        )
+        .expect("Strength is documented as \"byte\"");
+        changed = true;
+    }
+
+    changed
+}
+
+async fn get_access_point(
+    sys_conn: &Arc<LocalConnection>,
+    path: &str,
+) -> Result<AccessPoint, Box<dyn Error + Send + Sync>> {
+    let props = networkmanager_proxy(sys_conn.clone(), path.to_string())
+        .get_all("org.freedesktop.NetworkManager.AccessPoint")
+        .await
+        .map_err(|e| describe_dbus_error("NetworkManager", e))?;
+
+    let mut access_point = AccessPoint::default();
+    update_from_access_point(&mut access_point, &props);
+
+    Ok(access_point)
+}
+
+/**
+ * Get everything we care about from NetworkManager at once.
+ */
+async fn get_network_status(
+    sys_conn: &Arc<LocalConnection>,
+) -> Result<NetworkStatus, Box<dyn Error + Send + Sync>> {
+    let (mut status, wifi_device) = get_primary_connection(sys_conn).await?;
+
+    if let Some(device) = wifi_device
+        && let Some(path) = get_active_access_point(sys_conn, &device).await?
+    {
+        status.access_point = Some(get_access_point(sys_conn, &path).await?);
+    }
+
+    Ok(status)
+}
+
+/**
+ * A PropertiesChanged match that follows one object around, e.g.
+ * whichever access point we're connected to right now.
+ */
+#[derive(Default)]
+struct RetargetableMatch {
+    target: Option<(String, MsgMatch)>,
+}
+
+impl RetargetableMatch {
+    fn path(&self) -> Option<&str> {
+        self.target
+            .as_ref()
+            .map(|(path, _prop_match)| path.as_str())
+    }
+
+    /**
+     * Point the match at a different object, or at nothing. If it's
+     * already pointed there, the callback is left alone.
+     */
+    async fn retarget(
+        &mut self,
+        sys_conn: &LocalConnection,
+        path: Option<String>,
+        cb: impl FnMut(Message, PropChange) -> bool + Send + 'static,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if self.path() == path.as_deref() {
+            return Ok(());
+        }
+
+        self.clear(sys_conn).await?;
+
+        if let Some(path) = path {
+            let rule = PropChange::match_rule(None, Some(&path.clone().into())).static_clone();
+            let prop_match = sys_conn.add_match(rule).await?.cb(cb);
+            self.target = Some((path, prop_match));
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Take the match off the bus. Just dropping it would leave the
+     * callback around, along with anything it holds on to.
+     */
+    async fn clear(
+        &mut self,
+        sys_conn: &LocalConnection,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some((_path, prop_match)) = self.target.take() {
+            sys_conn.remove_match(prop_match.token()).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/**
+ * What the NetworkManager tracker should go get.
+ */
+enum NetworkRefetch {
+    /**
+     * The primary connection changed, so anything could have.
+     */
+    Everything,
+    /**
+     * The Wi-Fi device moved to a different access point.
+     */
+    AccessPoint,
+    /**
+     * The access point's properties got invalidated.
+     */
+    AccessPointProps,
+}
+
+/**
+ * Keep track of the primary connection, and if it's Wi-Fi, of the
+ * access point it's using. Since which objects we need to watch can
+ * change, we move our matches around from here, and not from the match
+ * callbacks, which can't reenter dbus.
+ *
+ * The callbacks on our own matches only get a weak sender, so that
+ * once the listener stops, this finishes and lets go of them.
+ */
+async fn track_network(
     sys_conn: Arc<LocalConnection>,
+    network: Arc<StdMutex<NetworkStatus>>,
+    refetch_tx: WeakUnboundedSender<NetworkRefetch>,
+    mut refetch_q: UnboundedReceiver<NetworkRefetch>,
     change_q: Sender<StatusbarChangeCause>,
-) -> Result<MsgMatch, Box<dyn Error>> {
-    let rule =
-        PropChange::match_rule(None, Some(&"/org/freedesktop/timedate1".into())).static_clone();
+) -> Result<(), Box<dyn Error>> {
+    let mut device_match = RetargetableMatch::default();
+    let mut ap_match = RetargetableMatch::default();
 
-    let timedate_proxy = Proxy::new(
-        "org.freedesktop.timedate1",
-        rule.path.clone().unwrap(),
-        Duration::from_secs(5),
-        sys_conn.clone(),
-    );
+    let tracked: Result<(), Box<dyn Error>> = async {
+        while let Some(what) = refetch_q.recv().await {
+            let fetched = follow_network(
+                &sys_conn,
+                &network,
+                what,
+                (&mut device_match, &mut ap_match),
+                &refetch_tx,
+                &change_q,
+            )
+            .await;
+            let got_net_when = Instant::now();
 
-    let iface = match Interface::new("org.freedesktop.timedate1") {
-        Ok(interface) => interface,
-        Err(_description) => {
-            unreachable!("This hardcoded name is the correct one so it must be okay.")
+            change_q
+                .send(StatusbarChangeCause::NetworkChange(MaybeData(
+                    fetched.map(|status| Some((got_net_when, status))),
+                )))
+                .await?;
         }
+
+        Ok(())
+    }
+    .await;
+
+    // The access point's callback sends changes straight to change_q,
+    // so if the connection is still up, it'd keep going without us.
+    let cleared = device_match
+        .clear(&sys_conn)
+        .await
+        .and(ap_match.clear(&sys_conn).await);
+
+    tracked?;
+    cleared.map_err(|e| e as Box<dyn Error>)
+}
+
+async fn follow_network(
+    sys_conn: &Arc<LocalConnection>,
+    network: &Arc<StdMutex<NetworkStatus>>,
+    what: NetworkRefetch,
+    (device_match, ap_match): (&mut RetargetableMatch, &mut RetargetableMatch),
+    refetch_tx: &WeakUnboundedSender<NetworkRefetch>,
+    change_q: &Sender<StatusbarChangeCause>,
+) -> Result<NetworkStatus, Box<dyn Error + Send + Sync>> {
+    let mut fresh = None;
+
+    if let NetworkRefetch::Everything = what {
+        let (status, wifi_device) = get_primary_connection(sys_conn).await?;
+        fresh = Some(status);
+
+        let cloned_refetch_tx = refetch_tx.clone();
+        device_match
+            .retarget(
+                sys_conn,
+                wifi_device,
+                move |_mesg: Message, change: PropChange| {
+                    if change.interface_name == "org.freedesktop.NetworkManager.Device.Wireless"
+                        && (change.changed_properties.contains_key("ActiveAccessPoint")
+                            || change
+                                .invalidated_properties
+                                .contains(&String::from("ActiveAccessPoint")))
+                        && let Some(refetch_tx) = cloned_refetch_tx.upgrade()
+                    {
+                        let _ = refetch_tx.send(NetworkRefetch::AccessPoint);
+                    }
+
+                    true
+                },
+            )
+            .await?;
+    }
+
+    if let NetworkRefetch::Everything | NetworkRefetch::AccessPoint = what {
+        let ap_path = match device_match.path() {
+            Some(device) => get_active_access_point(sys_conn, device).await?,
+            None => None,
+        };
+
+        let cloned_refetch_tx = refetch_tx.clone();
+        let cloned_network = network.clone();
+        let cloned_change_q = change_q.clone();
+        ap_match
+            .retarget(
+                sys_conn,
+                ap_path,
+                move |_mesg: Message, change: PropChange| {
+                    if change.interface_name == "org.freedesktop.NetworkManager.AccessPoint" {
+                        if change
+                            .invalidated_properties
+                            .iter()
+                            .any(|prop| ["Ssid", "Strength"].contains(&prop.as_str()))
+                            && let Some(refetch_tx) = cloned_refetch_tx.upgrade()
+                        {
+                            let _ = refetch_tx.send(NetworkRefetch::AccessPointProps);
+                        }
+
+                        let mut status = cloned_network
+                            .lock()
+                            .expect("Nothing should panic while holding this.");
+
+                        if let Some(access_point) = &mut status.access_point
+                            && update_from_access_point(access_point, &change.changed_properties)
+                        {
+                            let got_net_when = Instant::now();
+                            spawn_local(wrangle_lifetimes_update(
+                                cloned_change_q.clone(),
+                                StatusbarChangeCause::NetworkChange(MaybeData(Ok(Some((
+                                    got_net_when,
+                                    status.clone(),
+                                ))))),
+                            ));
+                        }
+                    }
+
+                    true
+                },
+            )
+            .await?;
+    }
+
+    let access_point = match ap_match.path() {
+        Some(path) => Some(get_access_point(sys_conn, path).await?),
+        None => None,
     };
 
-    let tz_name_member = match Member::new("Timezone") {
-        Ok(member) => member,
-        Err(_description) => {
-            unreachable!("This hardcoded name is the correct one so it must be okay.")
+    // The match callbacks might have updated the connectivity while we
+    // were busy, so only replace what we went and got.
+    let mut status = network
+        .lock()
+        .expect("Nothing should panic while holding this.");
+    if let Some(fresh) = fresh {
+        *status = fresh;
+    }
+    status.access_point = access_point;
+
+    Ok(status.clone())
+}
+
+async fn listen_to_networkmanager(
+    sys_conn: Arc<LocalConnection>,
+    change_q: Sender<StatusbarChangeCause>,
+) -> Result<BusListener, Box<dyn Error>> {
+    let rule = PropChange::match_rule(None, Some(&NETWORKMANAGER_PATH.into())).static_clone();
+
+    // Like for UPower, PropertiesChanged only tells us about some of
+    // what we show.
+    let network = Arc::new(StdMutex::new(NetworkStatus::default()));
+
+    let cloned_change_q = change_q.clone();
+    let cloned_network = network.clone();
+    let (refetch_tx, refetch_rx) = unbounded_channel();
+    let cloned_refetch_tx = refetch_tx.clone();
+
+    let prop_match =
+        sys_conn
+            .add_match(rule)
+            .await?
+            .cb(move |_mesg: Message, change: PropChange| {
+                if change.interface_name == "org.freedesktop.NetworkManager" {
+                    let mentioned = |prop: &str| {
+                        change.changed_properties.contains_key(prop)
+                            || change.invalidated_properties.iter().any(|p| p == prop)
+                    };
+
+                    // A new primary connection means going to look at
+                    // it, which gets the connectivity too.
+                    if mentioned("PrimaryConnection")
+                        || mentioned("PrimaryConnectionType")
+                        || change
+                            .invalidated_properties
+                            .contains(&String::from("Connectivity"))
+                    {
+                        // If the tracker is gone, it already reported why.
+                        let _ = cloned_refetch_tx.send(NetworkRefetch::Everything);
+                    } else if let Some(connectivity) = change.changed_properties.get("Connectivity")
+                    {
+                        let mut status = cloned_network
+                            .lock()
+                            .expect("Nothing should panic while holding this.");
+
+                        status.connectivity = Connectivity::from(
+                            u32::try_from(
+                                connectivity
+                                    .as_u64()
+                                    .expect("Connectivity is documented as \"uint32\""),
+                            )
+                            .expect("Connectivity is documented as \"uint32\""),
+                        );
+
+                        let got_net_when = Instant::now();
+                        spawn_local(wrangle_lifetimes_update(
+                            cloned_change_q.clone(),
+                            StatusbarChangeCause::NetworkChange(MaybeData(Ok(Some((
+                                got_net_when,
+                                status.clone(),
+                            ))))),
+                        ));
+                    }
+                }
+
+                true
+            });
+
+    // Same as UPower, NetworkManager might not be running yet, or
+    // might go away.
+    let cloned_change_q = change_q.clone();
+    let cloned_refetch_tx = refetch_tx.clone();
+
+    let owner_watch = watch_name_owner(
+        &sys_conn,
+        "org.freedesktop.NetworkManager",
+        move |has_owner| {
+            if has_owner {
+                let _ = cloned_refetch_tx.send(NetworkRefetch::Everything);
+            } else {
+                spawn_local(wrangle_lifetimes_update(
+                    cloned_change_q.clone(),
+                    StatusbarChangeCause::NetworkChange(MaybeData(Err(Box::new(
+                        SourceUnavailable("NetworkManager"),
+                    )))),
+                ));
+            }
+        },
+    )
+    .await?;
+
+    spawn_local(track_network(
+        sys_conn,
+        network,
+        refetch_tx.downgrade(),
+        refetch_rx,
+        change_q,
+    ));
+
+    // Get the starting status.
+    let _ = refetch_tx.send(NetworkRefetch::Everything);
+
+    Ok(BusListener {
+        signal_match: prop_match,
+        owner_watch: Some(owner_watch),
+    })
+}
+
+async fn get_timezone(
+    timedate_proxy: &Proxy<'static, Arc<LocalConnection>>,
+    iface: &Interface<'static>,
+    tz_name_member: &Member<'static>,
+) -> Result<Tz, Box<dyn Error + Send + Sync>> {
+    let tz_str = timedate_proxy
+        .get::<String>(iface, tz_name_member)
+        .await
+        .map_err(|e| describe_dbus_error("timedate1", e))?;
+
+    Ok(tz_str.parse::<Tz>()?)
+}
+
+/**
+ * Report a timezone we just got. The ticker has to hear about it too,
+ * since hours and days start at different times in different zones.
+ */
+fn timezone_change(
+    fetched: Result<Tz, Box<dyn Error + Send + Sync>>,
+    tick_tx: &UnboundedSender<TickRequest>,
+) -> StatusbarChangeCause {
+    let got_tz_when = Instant::now();
+
+    if let Ok(tz) = &fetched {
+        // If the ticker is gone, it already reported why.
+        let _ = tick_tx.send(TickRequest::SetTimezone(*tz));
+    }
+
+    StatusbarChangeCause::TzChange(MaybeData(fetched.map(|tz| Some((got_tz_when, tz)))))
+}
+
+/**
+ * Go fetch the timezone whenever we don't know it, e.g. when it gets
+ * invalidated. Like for UPower, we can't do this from the match
+ * callback. If timedated isn't around at all, we look at the system
+ * zone files ourselves, and watch them until it shows up.
+ */
+async fn refetch_timezone(
+    timedate_proxy: Proxy<'static, Arc<LocalConnection>>,
+    iface: Interface<'static>,
+    tz_name_member: Member<'static>,
+    mut refetch_q: UnboundedReceiver<()>,
+    change_q: Sender<StatusbarChangeCause>,
+    tick_tx: UnboundedSender<TickRequest>,
+) -> Result<(), Box<dyn Error>> {
+    let mut fallback_watch: Option<DirectoryWatch> = None;
+
+    loop {
+        // The zone files changing is as good as a refetch request.
+        let wakeup = match &fallback_watch {
+            Some(watch) => {
+                let mut changed = pin!(watch.changed());
+                poll_fn(|cx| {
+                    if let Poll::Ready(request) = refetch_q.poll_recv(cx) {
+                        return Poll::Ready(Ok(request.is_some()));
+                    }
+
+                    changed.as_mut().poll(cx).map(|res| res.map(|()| true))
+                })
+                .await
+            }
+            None => Ok(refetch_q.recv().await.is_some()),
+        };
+
+        match wakeup {
+            Ok(true) => {}
+            Ok(false) => break,
+            // Look again, and set up a new watch if we still need one.
+            Err(_) => fallback_watch = None,
         }
-    };
+
+        let fetched = match get_timezone(&timedate_proxy, &iface, &tz_name_member).await {
+            Err(e) if e.is::<SourceUnavailable>() => {
+                // Start watching before we look, like
+                // listen_for_local_tzchange does.
+                if fallback_watch.is_none() {
+                    fallback_watch = LocalTimezone::System
+                        .watched_files()
+                        .and_then(|(dir, names)| DirectoryWatch::new(&dir, &names).ok());
+                }
+
+                LocalTimezone::System.resolve()
+            }
+            fetched => {
+                fallback_watch = None;
+                fetched
+            }
+        };
+
+        change_q.send(timezone_change(fetched, &tick_tx)).await?;
+    }
+
+    Ok(())
+}
+
+async fn listen_for_tzchange(
+    sys_conn: Arc<LocalConnection>,
+    change_q: Sender<StatusbarChangeCause>,
+    tick_tx: UnboundedSender<TickRequest>,
+) -> Result<BusListener, Box<dyn Error>> {
+    let rule = PropChange::match_rule(None, Some(&TIMEDATE_PATH.into())).static_clone();
+
+    let timedate_proxy = timedate_proxy(sys_conn.clone());
+    let (iface, tz_name_member) = timedate_names();
 
     // TODO: go introspect and make sure that Timezone is marked emits-change.
 
     let cloned_change_q = change_q.clone();
+    let cloned_tick_tx = tick_tx.clone();
+    let (refetch_tx, refetch_rx) = unbounded_channel();
+    let cloned_refetch_tx = refetch_tx.clone();
+
+    let prop_match =
+        sys_conn
+            .add_match(rule)
+            .await?
+            .cb(move |_mesg: Message, change: PropChange| {
+                if change.interface_name == "org.freedesktop.timedate1" {
+                    let maybe_new_tz = {
+                        if let Some(new_tz_str) = change.changed_properties.get("Timezone") {
+                            Some(
+                                new_tz_str
+                                    .as_str()
+                                    .expect("Timezone is documented as a string")
+                                    .parse::<Tz>()
+                                    .map_err(|e| e.into()),
+                            )
+                        } else if change
+                            .invalidated_properties
+                            .contains(&String::from("Timezone"))
+                        {
+                            // If the refetcher is gone, it already reported why.
+                            let _ = cloned_refetch_tx.send(());
+                            None
+                        } else {
+                            None
+                        }
+                    };
 
-    let mtch = sys_conn.add_match(rule).await?.cb(move |_mesg: Message, change: PropChange| {
-        if change.interface_name == "org.freedesktop.timedate1" {
-            let maybe_new_tz = {
-                if let Some(new_tz_str) = change.changed_properties.get("Timezone") {
-                    Some(new_tz_str.as_str().expect("Timezone is documented as a string").parse::<Tz>().expect("expected to recognize timezone name"))
-                } else if change.invalidated_properties.contains(&String::from("Timezone")) {
-                    unimplemented!("Firing off a message here would reenter dbus and I haven't thought that out yet.");
-                } else {
-                    None
+                    if let Some(new_tz) = maybe_new_tz {
+                        spawn_local(wrangle_lifetimes_update(
+                            cloned_change_q.clone(),
+                            timezone_change(new_tz, &cloned_tick_tx),
+                        ));
+                    }
                 }
-            };
 
-            if let Some(new_tz) = maybe_new_tz {
-                let got_tz_when = Instant::now();
-                spawn_local(wrangle_lifetimes_update(cloned_change_q.clone(), StatusbarChangeCause::TzChange(MaybeData(Ok(Some((got_tz_when, new_tz)))))));
-            }
+                true
+            });
+
+    // timedated exits when it's idle, and gets activated again when
+    // needed, so it going away isn't a problem. It showing up might
+    // mean it wasn't around when we first asked, though.
+    let cloned_refetch_tx = refetch_tx.clone();
+
+    let owner_watch = watch_name_owner(&sys_conn, "org.freedesktop.timedate1", move |has_owner| {
+        if has_owner {
+            let _ = cloned_refetch_tx.send(());
         }
+    })
+    .await?;
 
-        true
-    });
+    spawn_local(refetch_timezone(
+        timedate_proxy,
+        iface,
+        tz_name_member,
+        refetch_rx,
+        change_q,
+        tick_tx,
+    ));
 
     // Get the starting TZ.
-    let start_tz_str = timedate_proxy
-        .get::<String>(&iface, &tz_name_member)
-        .await?;
-    let got_tz_when = Instant::now();
-    let start_tz = start_tz_str.parse::<Tz>()?;
+    let _ = refetch_tx.send(());
 
-    change_q
-        .send(StatusbarChangeCause::TzChange(MaybeData(Ok(Some((
-            got_tz_when,
-            start_tz,
-        ))))))
-        .await?;
+    Ok(BusListener {
+        signal_match: prop_match,
+        owner_watch: Some(owner_watch),
+    })
+}
+
+/**
+ * Ask for the timer to be set again when we wake up from suspend. The
+ * clock jumping forward over the time we were asleep doesn't always
+ * cancel the timer, so we could be stuck showing the time we went to
+ * sleep at.
+ */
+async fn listen_for_resume(
+    sys_conn: Arc<LocalConnection>,
+    tick_tx: UnboundedSender<TickRequest>,
+) -> Result<BusListener, Box<dyn Error>> {
+    let rule = MatchRule::new_signal("org.freedesktop.login1.Manager", "PrepareForSleep")
+        .with_sender("org.freedesktop.login1")
+        .with_path("/org/freedesktop/login1")
+        .static_clone();
+
+    let signal_match =
+        sys_conn
+            .add_match(rule)
+            .await?
+            .cb(move |_mesg: Message, (going_to_sleep,): (bool,)| {
+                if !going_to_sleep {
+                    // If the ticker is gone, it already reported why.
+                    let _ = tick_tx.send(TickRequest::Resync);
+                }
+
+                true
+            });
 
-    Ok(mtch)
+    // If logind isn't around, nobody's going to suspend us through it,
+    // so there's nothing to fetch when it shows up.
+    Ok(BusListener {
+        signal_match,
+        owner_watch: None,
+    })
+}
+
+/**
+ * Set up all of our matches on a fresh system bus connection. If any of
+ * them fail, we stop listening to the ones that worked, so nothing is
+ * left holding onto the connection. Our data sources not being around
+ * isn't a failure here, they each report that on their own.
+ */
+async fn listen_on_system_bus(
+    sys_conn: Arc<LocalConnection>,
+    change_q: Sender<StatusbarChangeCause>,
+    watch_timezone: bool,
+    tick_tx: UnboundedSender<TickRequest>,
+) -> Result<Vec<BusListener>, Box<dyn Error>> {
+    let mut listeners = Vec::new();
+
+    let listening = async || {
+        listeners.push(listen_to_upower(sys_conn.clone(), change_q.clone()).await?);
+        listeners.push(listen_for_resume(sys_conn.clone(), tick_tx.clone()).await?);
+
+        if watch_timezone {
+            listeners.push(listen_for_tzchange(sys_conn.clone(), change_q, tick_tx).await?);
+        }
+
+        Ok::<(), Box<dyn Error>>(())
+    };
+
+    match listening().await {
+        Ok(()) => Ok(listeners),
+        Err(e) => {
+            for listener in listeners {
+                listener.stop(&sys_conn);
+            }
+            Err(e)
+        }
+    }
+}
+
+/**
+ * Wait until we lose the connection to the system bus. Meanwhile, we
+ * only listen to NetworkManager while the config has a network block.
+ */
+async fn wait_on_system_bus(
+    sys_conn: &Arc<LocalConnection>,
+    change_q: &Sender<StatusbarChangeCause>,
+    system_handle: &mut JoinHandle<IOResourceError>,
+    network_wanted: &mut WatchReceiver<bool>,
+    network_listener: &mut Option<BusListener>,
+) -> Result<Result<IOResourceError, JoinError>, Box<dyn Error>> {
+    // Once the config can't change anymore, stop checking.
+    let mut config_open = true;
+
+    loop {
+        let wanted = *network_wanted.borrow_and_update();
+        match (wanted, network_listener.take()) {
+            (true, None) => {
+                *network_listener =
+                    Some(listen_to_networkmanager(sys_conn.clone(), change_q.clone()).await?);
+            }
+            (false, Some(listener)) => listener.stop(sys_conn),
+            (_, listener) => *network_listener = listener,
+        }
+
+        let mut changed = pin!(network_wanted.changed());
+        let lost = poll_fn(|cx| {
+            if let Poll::Ready(joined) = Pin::new(&mut *system_handle).poll(cx) {
+                return Poll::Ready(Some(joined));
+            }
+
+            if config_open && let Poll::Ready(still_open) = changed.as_mut().poll(cx) {
+                config_open = still_open.is_ok();
+                return Poll::Ready(None);
+            }
+
+            Poll::Pending
+        })
+        .await;
+
+        if let Some(joined) = lost {
+            return Ok(joined);
+        }
+    }
+}
+
+const MIN_RECONNECT_DELAY: Duration = Duration::from_millis(250);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+
+/**
+ * Keep us connected to the system bus, with our matches on it. When we
+ * lose the connection (e.g. dbus-broker restarts during an update), we
+ * mark what we got from it as stale, and try to reconnect with
+ * exponential backoff.
+ */
+async fn supervise_system_bus(
+    change_q: Sender<StatusbarChangeCause>,
+    watch_timezone: bool,
+    tick_tx: UnboundedSender<TickRequest>,
+    mut network_wanted: WatchReceiver<bool>,
+) -> Result<Infallible, Box<dyn Error>> {
+    let mut reconnect_delay = MIN_RECONNECT_DELAY;
+
+    loop {
+        // TODO: Do we want to connect to the session bus?
+
+        let problem = match connection::new_system_local() {
+            Err(e) => format!("Can't connect to system D-Bus: {}", e),
+            Ok((sys_resource, sys_conn)) => {
+                // Start our resource tracker task, to see if we lose connection.
+                let mut system_handle = spawn_local(sys_resource);
+
+                // Our name owner watches share a match rule, so every
+                // filter that matches needs to see the signal.
+                sys_conn.set_signal_match_mode(true);
+
+                match listen_on_system_bus(
+                    sys_conn.clone(),
+                    change_q.clone(),
+                    watch_timezone,
+                    tick_tx.clone(),
+                )
+                .await
+                {
+                    Ok(listeners) => {
+                        // We're up and running, so start backing off
+                        // from scratch next time.
+                        reconnect_delay = MIN_RECONNECT_DELAY;
+
+                        let mut network_listener = None;
+                        let lost = wait_on_system_bus(
+                            &sys_conn,
+                            &change_q,
+                            &mut system_handle,
+                            &mut network_wanted,
+                            &mut network_listener,
+                        )
+                        .await;
+
+                        // Drop the callbacks, so the tasks waiting on
+                        // them can finish and let go of the connection.
+                        for listener in listeners.into_iter().chain(network_listener) {
+                            listener.stop(&sys_conn);
+                        }
+
+                        match lost {
+                            Ok(joined) => format!("Lost connection to system D-Bus: {}", joined?),
+                            Err(e) => {
+                                system_handle.abort();
+                                format!("Can't listen on system D-Bus: {}", e)
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        system_handle.abort();
+                        format!("Can't listen on system D-Bus: {}", e)
+                    }
+                }
+            }
+        };
+
+        change_q
+            .send(StatusbarChangeCause::SystemBusLost {
+                problem: problem.into(),
+                timezone_too: watch_timezone,
+            })
+            .await?;
+
+        sleep(reconnect_delay).await;
+        reconnect_delay = (reconnect_delay * 2).min(MAX_RECONNECT_DELAY);
+    }
+}
+
+/**
+ * Keep track of the timezone without timedated, by looking at `TZ` or
+ * the system zone files, and watching them for changes.
+ */
+async fn listen_for_local_tzchange(
+    local_tz: LocalTimezone,
+    change_q: Sender<StatusbarChangeCause>,
+    tick_tx: UnboundedSender<TickRequest>,
+) -> Result<(), Box<dyn Error>> {
+    // Start watching before we look, so we can't miss a change in
+    // between.
+    let watch = local_tz
+        .watched_files()
+        .map(|(dir, names)| DirectoryWatch::new(&dir, &names));
+
+    loop {
+        let resolved = local_tz.resolve();
+
+        change_q.send(timezone_change(resolved, &tick_tx)).await?;
+
+        match &watch {
+            Some(Ok(watch)) => watch.changed().await?,
+            // A zone named in TZ can't change under us. If we can't
+            // watch the zone files (e.g. their directory is missing),
+            // what we just sent is as good as it gets.
+            Some(Err(_)) | None => return Ok(()),
+        }
+    }
+}
+
+/**
+ * Load the config again whenever the file changes. If the directory
+ * it would be in doesn't exist, there's nothing to watch, so we stick
+ * with what we loaded at startup.
+ */
+async fn reload_config(
+    location: ConfigLocation,
+    change_q: Sender<StatusbarChangeCause>,
+    tick_tx: UnboundedSender<TickRequest>,
+    network_wanted: WatchSender<bool>,
+) -> Result<(), Box<dyn Error>> {
+    let (Some(dir), Some(name)) = (
+        location.path.parent(),
+        location.path.file_name().and_then(|name| name.to_str()),
+    ) else {
+        return Ok(());
+    };
+
+    // Editors that write in place create the file empty first, and an
+    // empty config is a valid one with no blocks, so wait until it's
+    // been written.
+    let watch = DirectoryWatch::new_for_written(dir, &[name.to_string()])?;
+
+    loop {
+        watch.changed().await?;
+
+        let loaded = location.load();
+
+        if let Ok(config) = &loaded {
+            // If the ticker is gone, it already reported why.
+            let _ = tick_tx.send(TickRequest::SetGranularity(config.tick_granularity()));
+            network_wanted.send_replace(config.shows_network());
+        }
+
+        change_q
+            .send(StatusbarChangeCause::ConfigReload(loaded))
+            .await?;
+    }
+}
+
+/**
+ * Figure out where the timezone should come from, if not timedated.
+ * `TZ` overrides the system timezone for us, so timedated's idea of it
+ * doesn't matter then. Without systemd, there's no timedated to ask.
+ */
+fn pick_local_timezone() -> Option<LocalTimezone> {
+    LocalTimezone::from_env()
+        .or_else(|| (!Path::new("/run/systemd/system").is_dir()).then_some(LocalTimezone::System))
 }
 
 struct TreatPossibleChangesConservatively<'a> {
@@ -200,12 +1381,12 @@ struct TreatPossibleChangesConservatively<'a> {
 }
 
 impl ClockTickCallbacks for TreatPossibleChangesConservatively<'_> {
-    async fn changed_minute(&self) -> Result<(), Box<dyn Error>> {
-        self.change_q.send(StatusbarChangeCause::NextMinute).await?;
+    async fn changed_tick(&self) -> Result<(), Box<dyn Error>> {
+        self.change_q.send(StatusbarChangeCause::NextTick).await?;
         Ok(())
     }
-    async fn minute_maybe_lost(&self) -> Result<(), Box<dyn Error>> {
-        self.change_q.send(StatusbarChangeCause::NextMinute).await?;
+    async fn tick_maybe_lost(&self) -> Result<(), Box<dyn Error>> {
+        self.change_q.send(StatusbarChangeCause::NextTick).await?;
         Ok(())
     }
     async fn adjustment_happened(&self) -> Result<(), Box<dyn Error>> {
@@ -214,11 +1395,20 @@ impl ClockTickCallbacks for TreatPossibleChangesConservatively<'_> {
             .await?;
         Ok(())
     }
+    async fn resumed_from_suspend(&self) -> Result<(), Box<dyn Error>> {
+        // The clock jumped forward over however long we were asleep.
+        self.change_q
+            .send(StatusbarChangeCause::ClockAdjust)
+            .await?;
+        Ok(())
+    }
 }
 
-async fn fire_on_next_minute(
+async fn fire_on_next_tick(
     change_q: Sender<StatusbarChangeCause>,
     io_ctx: Rc<Mutex<StatusbarIOContext<'_>>>,
+    granularity: TickGranularity,
+    tick_q: UnboundedReceiver<TickRequest>,
 ) -> Result<Infallible, Box<dyn Error>> {
     // TODO this should return Result<!, ...>
 
@@ -226,7 +1416,7 @@ async fn fire_on_next_minute(
         change_q: &change_q,
     };
 
-    tick_every_minute(io_ctx, &cb).await
+    tick_clock(io_ctx, &cb, granularity, tick_q).await
 }
 
 use std::os::fd::OwnedFd;
@@ -245,43 +1435,93 @@ fn get_output(
     Ok(Box::new(tokio_io::stdout()))
 }
 
-async fn task_setup(out_to_sway: OwnedFd) -> Result<(), Box<dyn Error>> {
-    let sender_to_sway = get_output(out_to_sway)?;
+fn get_input(in_from_sway: OwnedFd) -> Box<dyn AsyncRead + Unpin> {
+    // Same as for output, our input might not be a pipe.
 
-    let local_tasks = tokio::task::LocalSet::new();
+    if let Ok(pipe_possibility) = pipe::Receiver::from_owned_fd(in_from_sway) {
+        return Box::new(pipe_possibility);
+    }
 
-    let io_ctx = Rc::new(Mutex::new(StatusbarIOContext::from(sender_to_sway)));
+    Box::new(tokio_io::stdin())
+}
 
-    // Connect to the system bus, since we want time, battery, &c. info.
-    let (sys_resource, sys_conn) = connection::new_system_local()?;
+async fn task_setup(
+    out_to_sway: OwnedFd,
+    in_from_sway: Option<OwnedFd>,
+    renderer: Box<dyn Renderer>,
+    config: Config,
+    config_location: Option<ConfigLocation>,
+) -> Result<(), Box<dyn Error>> {
+    let sender_to_sway = get_output(out_to_sway)?;
 
-    // TODO: Do we want to connect to the session bus?
+    // Get ready for the bar to tell us it's hidden before we tell it
+    // which signals to use.
+    let stop_cont_signals = StopContSignals::install()?;
 
-    // Start our resource tracker task, to see if we lose connection.
-    let _system_handle = local_tasks.spawn_local(async {
-        let err = sys_resource.await;
-        panic!("Lost connection to system D-Bus: {}", err);
-    });
+    let local_tasks = LocalSet::new();
+
+    let io_ctx = Rc::new(Mutex::new(StatusbarIOContext::from(sender_to_sway)));
 
     // Make the channel, with a totally arbitrary depth.
     let (tx, rx) = channel(32);
 
-    let upow_connect = local_tasks.spawn_local(listen_to_upower(sys_conn.clone(), tx.clone()));
-    let tz_connect = local_tasks.spawn_local(listen_for_tzchange(sys_conn.clone(), tx.clone()));
+    let local_tz = pick_local_timezone();
 
-    let _tick_minute = local_tasks.spawn_local(fire_on_next_minute(tx.clone(), io_ctx.clone()));
+    // For asking the ticker to set its timer again, e.g. after resume,
+    // or to tick at a different pace once the config changes.
+    let (tick_tx, tick_rx) = unbounded_channel();
 
-    let _update_stat = local_tasks.spawn_local(run_statusbar_updater(rx, io_ctx));
+    // Whether the config wants us to keep up with NetworkManager.
+    let (network_tx, network_rx) = watch_channel(config.shows_network());
 
-    let upow_unlisten_match = local_tasks.run_until(upow_connect).await??;
-    let tz_unlisten_match = local_tasks.run_until(tz_connect).await??;
+    // Connect to the system bus, since we want time, battery, &c. info.
+    let _system_bus = local_tasks.spawn_local(supervise_system_bus(
+        tx.clone(),
+        local_tz.is_none(),
+        tick_tx.clone(),
+        network_rx,
+    ));
+
+    if let Some(local_tz) = local_tz {
+        let _local_tz = local_tasks.spawn_local(listen_for_local_tzchange(
+            local_tz,
+            tx.clone(),
+            tick_tx.clone(),
+        ));
+    }
+
+    let _tick = local_tasks.spawn_local(fire_on_next_tick(
+        tx.clone(),
+        io_ctx.clone(),
+        config.tick_granularity(),
+        tick_rx,
+    ));
+
+    if let Some(in_from_sway) = in_from_sway.filter(|_| renderer.reads_clicks()) {
+        let _read_clicks = local_tasks.spawn_local(read_click_events(
+            get_input(in_from_sway),
+            tx.clone(),
+            io_ctx.clone(),
+        ));
+    }
+
+    if let Some(config_location) = config_location {
+        let _reload_config = local_tasks.spawn_local(reload_config(
+            config_location,
+            tx.clone(),
+            tick_tx,
+            network_tx,
+        ));
+    }
+
+    let _forward_stop_cont =
+        local_tasks.spawn_local(forward_stop_cont(stop_cont_signals, tx.clone()));
+
+    let _update_stat = local_tasks.spawn_local(run_statusbar_updater(rx, io_ctx, config, renderer));
 
     // Wait for our tasks to finish.
     local_tasks.await;
 
-    sys_conn.remove_match(upow_unlisten_match.token()).await?;
-    sys_conn.remove_match(tz_unlisten_match.token()).await?;
-
     Ok(())
 }
 
@@ -289,9 +1529,127 @@ use nix::fcntl::{OFlag, open};
 use nix::sys::prctl::set_timerslack;
 use nix::sys::stat::Mode;
 
-pub fn main() -> Result<(), Box<dyn Error>> {
-    // Set a vague guess at a decent slack.
-    set_timerslack(7_500_000u64)?;
+/**
+ * Gather everything once, print a single status line, and exit. We
+ * don't care about changes, so there's no need for match rules or
+ * timers.
+ */
+async fn run_once(renderer: Box<dyn Renderer>, config: Config) -> Result<(), Box<dyn Error>> {
+    let local_tz = pick_local_timezone();
+    let mut data = StatusbarData::new();
+
+    if let Some(local_tz) = &local_tz {
+        data.update_timezone_result(local_tz.resolve());
+    }
+
+    match connection::new_system_local() {
+        Ok((sys_resource, sys_conn)) => {
+            let system_handle = spawn_local(sys_resource);
+
+            data.update_battery_result(get_upower_status(&upower_proxy(sys_conn.clone())).await);
+            if config.shows_network() {
+                data.update_network_result(get_network_status(&sys_conn).await);
+            }
+
+            if local_tz.is_none() {
+                let (iface, tz_name_member) = timedate_names();
+                data.update_timezone_result(
+                    get_timezone(&timedate_proxy(sys_conn), &iface, &tz_name_member).await,
+                );
+            }
+
+            system_handle.abort();
+        }
+        Err(e) => {
+            let problem = format!("Can't connect to system D-Bus: {}", e);
+
+            data.update_battery_result(Err(problem.clone().into()));
+            data.update_network_result(Err(problem.clone().into()));
+            if local_tz.is_none() {
+                data.update_timezone_result(Err(problem.into()));
+            }
+        }
+    }
+
+    let line = renderer.single_status_line(&status_blocks(&data, &config, renderer.as_ref()));
+
+    std::io::stdout().write_all(line.as_bytes())?;
+
+    Ok(())
+}
+
+/**
+ * Check that the config loads, and say how it went.
+ */
+fn check_config(location: Option<&ConfigLocation>) -> ExitCode {
+    let Some(location) = location else {
+        println!("No config location, using the built-in layout.");
+        return ExitCode::SUCCESS;
+    };
+
+    match location.load() {
+        Ok(_config) => {
+            println!("{}: okay", location.path.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}: {}", location.path.display(), e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+pub fn main() -> Result<ExitCode, Box<dyn Error>> {
+    let options = match cli::parse_args(std::env::args_os().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprint!("ssstatus-rs: {}\n\n{}", e, cli::USAGE);
+            return Ok(ExitCode::from(2));
+        }
+    };
+
+    if options.help {
+        print!("{}", cli::USAGE);
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if options.version {
+        println!("ssstatus-rs {}", env!("CARGO_PKG_VERSION"));
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let config_location = ConfigLocation::find(
+        options
+            .config
+            .clone()
+            .or_else(|| std::env::var_os("SSSTATUS_CONFIG").map(PathBuf::from)),
+    );
+
+    if options.check_config {
+        return Ok(check_config(config_location.as_ref()));
+    }
+
+    set_timerslack(options.timerslack)?;
+
+    // Load the config before touching the bar, so a broken one doesn't
+    // leave it with half a protocol.
+    let config = match &config_location {
+        Some(location) => location.load().map_err(|e| e as Box<dyn Error>)?,
+        None => Config::default(),
+    };
+
+    let renderer = renderer_for(&options);
+
+    if options.once {
+        Builder::new_current_thread()
+            .enable_io()
+            .enable_time()
+            .build()
+            .unwrap()
+            .block_on(LocalSet::new().run_until(run_once(renderer, config)))?;
+
+        return Ok(ExitCode::SUCCESS);
+    }
 
     // Right now, stdout is a pipe to sway. Let's open a copy as
     // nonblocking, since that's much safer than messing with the
@@ -306,11 +1664,33 @@ pub fn main() -> Result<(), Box<dyn Error>> {
         Mode::empty(),
     )?;
 
+    // Clicks come in on stdin. If that's closed, we just won't get any.
+    let in_from_sway = open(
+        "/proc/self/fd/0",
+        OFlag::empty()
+            .union(OFlag::O_RDONLY)
+            .union(OFlag::O_NONBLOCK)
+            .union(OFlag::O_CLOEXEC),
+        Mode::empty(),
+    )
+    .ok();
+
     Builder::new_current_thread()
         .enable_io()
         .enable_time()
         .thread_keep_alive(Duration::from_secs(70))
         .build()
         .unwrap()
-        .block_on(task_setup(out_to_sway))
+        .block_on(task_setup(
+            out_to_sway,
+            in_from_sway,
+            renderer,
+            config,
+            config_location,
+        ))?;
+
+    Ok(ExitCode::SUCCESS)
 }
+
+#[cfg(test)]
+mod maintests;
@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use dbus::Message;
+use dbus::arg::{Append, Arg, RefArg, Variant};
+
+use crate::data::battery::{BatteryState, BatteryStatus};
+use crate::{refetched_props, update_from_upower};
+
+/**
+ * A reply to `org.freedesktop.DBus.Properties.Get`, which always wraps
+ * the value in a variant.
+ */
+#[cfg(test)]
+fn get_reply<T: Arg + Append>(value: T) -> Message {
+    let mut call = Message::new_method_call(
+        "org.freedesktop.UPower",
+        "/org/freedesktop/UPower/devices/DisplayDevice",
+        "org.freedesktop.DBus.Properties",
+        "Get",
+    )
+    .unwrap();
+    // Replies have to point back at a call that was sent.
+    call.set_serial(1);
+
+    Message::new_method_return(&call)
+        .unwrap()
+        .append1(Variant(value))
+}
+
+#[test]
+fn check_refetched_upower_props() {
+    let mut status = BatteryStatus::default();
+
+    // This is what `Properties::get::<Box<dyn RefArg>>` reads out of the
+    // reply. Asking for another variant inside can't work.
+    let reply = get_reply(42.5f64);
+    assert!(reply.read1::<Variant<Variant<Box<dyn RefArg>>>>().is_err());
+    let value = reply.read1::<Variant<Box<dyn RefArg>>>().unwrap().0;
+
+    assert!(update_from_upower(
+        &mut status,
+        &refetched_props("Percentage".to_string(), value)
+    ));
+    assert_eq!(status.percentage, 42.5);
+
+    let value = get_reply(1u32)
+        .read1::<Variant<Box<dyn RefArg>>>()
+        .unwrap()
+        .0;
+
+    assert!(update_from_upower(
+        &mut status,
+        &refetched_props("State".to_string(), value)
+    ));
+    assert_eq!(status.state, BatteryState::Charging);
+    assert_eq!(status.percentage, 42.5);
+}
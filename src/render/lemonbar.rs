@@ -0,0 +1,122 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::render::Renderer;
+use crate::render::text::single_line;
+use crate::swaybar::Block;
+
+const SEPARATOR: &str = " | ";
+
+/**
+ * Escape a tag character in text by doubling it, so e.g. a '%' in an
+ * error message doesn't start a lemonbar tag.
+ */
+fn escape_tag_char(text: &str, tag_char: char) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if c == tag_char {
+            escaped.push(tag_char);
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+/**
+ * Our colors are `#RRGGBB` or `#RRGGBBAA`, like i3bar wants them.
+ * lemonbar wants the alpha first, and dzen2 doesn't do alpha at all.
+ */
+fn lemonbar_color(color: &str) -> String {
+    match color.len() {
+        9 => format!("#{}{}", &color[7..], &color[1..7]),
+        _ => color.to_string(),
+    }
+}
+
+fn dzen2_color(color: &str) -> &str {
+    color.get(..7).unwrap_or(color)
+}
+
+/**
+ * Lines with lemonbar formatting tags. Clicking a block makes lemonbar
+ * print its name.
+ */
+pub struct LemonbarRenderer;
+
+impl Renderer for LemonbarRenderer {
+    fn status_line(&self, blocks: &[Block]) -> String {
+        let mut line = blocks
+            .iter()
+            .map(|block| {
+                let mut text = escape_tag_char(&single_line(&block.full_text), '%');
+
+                if block.urgent == Some(true) {
+                    text = format!("%{{R}}{}%{{R}}", text);
+                }
+                if let Some(background) = &block.background {
+                    text = format!("%{{B{}}}{}%{{B-}}", lemonbar_color(background), text);
+                }
+                if let Some(color) = &block.color {
+                    text = format!("%{{F{}}}{}%{{F-}}", lemonbar_color(color), text);
+                }
+                if let Some(name) = &block.name {
+                    // ':' ends the command, and our names never have one.
+                    text = format!("%{{A:{}:}}{}%{{A}}", name, text);
+                }
+
+                text
+            })
+            .collect::<Vec<_>>()
+            .join(SEPARATOR);
+        line.push('\n');
+
+        line
+    }
+}
+
+/**
+ * Lines with dzen2 formatting commands.
+ */
+pub struct Dzen2Renderer;
+
+impl Renderer for Dzen2Renderer {
+    fn status_line(&self, blocks: &[Block]) -> String {
+        let mut line = blocks
+            .iter()
+            .map(|block| {
+                let mut text = escape_tag_char(&single_line(&block.full_text), '^');
+
+                if let Some(background) = &block.background {
+                    text = format!("^bg({}){}^bg()", dzen2_color(background), text);
+                }
+                if let Some(color) = &block.color {
+                    text = format!("^fg({}){}^fg()", dzen2_color(color), text);
+                }
+
+                text
+            })
+            .collect::<Vec<_>>()
+            .join(SEPARATOR);
+        line.push('\n');
+
+        line
+    }
+}
@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::render::Renderer;
+use crate::render::lemonbar::*;
+use crate::swaybar::Block;
+
+#[cfg(test)]
+fn some_blocks() -> Vec<Block> {
+    vec![
+        Block {
+            name: Some("battery".to_string()),
+            color: Some("#FF000080".to_string()),
+            urgent: Some(true),
+            ..Block::new(" 5%-".to_string())
+        },
+        Block {
+            name: Some("clock".to_string()),
+            background: Some("#000000".to_string()),
+            ..Block::new("^_^ 12:34".to_string())
+        },
+    ]
+}
+
+#[test]
+fn check_lemonbar() {
+    assert_eq!(
+        LemonbarRenderer.status_line(&some_blocks()),
+        "%{A:battery:}%{F#80FF0000}%{R} 5%%-%{R}%{F-}%{A} | \
+         %{A:clock:}%{B#000000}^_^ 12:34%{B-}%{A}\n"
+    );
+}
+
+#[test]
+fn check_dzen2() {
+    assert_eq!(
+        Dzen2Renderer.status_line(&some_blocks()),
+        "^fg(#FF0000) 5%-^fg() | ^bg(#000000)^^_^^ 12:34^bg()\n"
+    );
+}
+
+#[test]
+fn check_tags_stay_on_one_line() {
+    let blocks = vec![Block::new("50%\r\nlow".to_string())];
+
+    assert_eq!(LemonbarRenderer.status_line(&blocks), "50%%  low\n");
+    assert_eq!(Dzen2Renderer.status_line(&blocks), "50%  low\n");
+}
@@ -0,0 +1,379 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::rc::Rc;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tokio::sync::mpsc::Receiver;
+
+use crate::cli::{Options, Protocol};
+use crate::config::{BlockConfig, BlockKind, Config};
+use crate::data::StatusbarChangeCause::{
+    self, BatteryChange, Click, ConfigReload, Cont, NetworkChange, Stop, SystemBusLost, TzChange,
+};
+use crate::data::battery::FormattedBatteryStatus;
+use crate::data::click::{BUTTON_LEFT, ClickEvent};
+use crate::data::network::FormattedNetworkStatus;
+use crate::data::{EscapeData, StatusbarData, no_escaping};
+use crate::io::StatusbarIOContext;
+use crate::swaybar::{Block, Markup, SwaybarRenderer, escape_pango, strip_pango};
+use crate::time::FormattedDTD;
+
+pub mod lemonbar;
+pub mod text;
+pub mod waybar;
+
+use lemonbar::{Dzen2Renderer, LemonbarRenderer};
+use text::{PlainTextRenderer, TmuxRenderer};
+use waybar::WaybarRenderer;
+
+/**
+ * Turns our blocks into whatever the thing on the other end of our
+ * output wants to read.
+ */
+pub trait Renderer {
+    /**
+     * What to print before the first status line.
+     */
+    fn header(&self) -> String {
+        String::new()
+    }
+
+    /**
+     * One status line, as part of the stream of them.
+     */
+    fn status_line(&self, blocks: &[Block]) -> String;
+
+    /**
+     * One status line on its own, for when we're only printing once.
+     */
+    fn single_status_line(&self, blocks: &[Block]) -> String {
+        self.status_line(blocks)
+    }
+
+    /**
+     * Whether whoever reads our output sends click events to our input.
+     */
+    fn reads_clicks(&self) -> bool {
+        false
+    }
+
+    /**
+     * Whether blocks with Pango markup get shown as such. Otherwise,
+     * the markup gets stripped from their formats.
+     */
+    fn understands_pango(&self) -> bool {
+        false
+    }
+}
+
+pub fn renderer_for(options: &Options) -> Box<dyn Renderer> {
+    match options.protocol {
+        Protocol::Swaybar => Box::new(SwaybarRenderer {
+            policy: options.escape,
+        }),
+        Protocol::Text => Box::new(PlainTextRenderer),
+        Protocol::Tmux => Box::new(TmuxRenderer),
+        Protocol::Lemonbar => Box::new(LemonbarRenderer),
+        Protocol::Dzen2 => Box::new(Dzen2Renderer),
+        Protocol::Waybar => Box::new(WaybarRenderer {
+            block: options.block.clone().unwrap_or_default(),
+            policy: options.escape,
+        }),
+    }
+}
+
+fn status_block(data: &StatusbarData, block_config: &BlockConfig, pango_output: bool) -> Block {
+    let mut color = block_config.color.clone();
+    let mut urgent = None;
+    let mut percentage = None;
+    let mut classes = Vec::new();
+
+    let mut markup = block_config.markup;
+    let mut block_config = Cow::Borrowed(block_config);
+
+    let escape: EscapeData = match markup {
+        Some(Markup::Pango) if pango_output => escape_pango,
+        Some(Markup::Pango) => {
+            // The output would show the markup as-is, so take it out,
+            // and leave what we fill in alone.
+            let stripped = block_config.to_mut();
+            stripped.format = strip_pango(&stripped.format);
+            stripped.short_format = strip_pango(&stripped.short_format);
+            markup = None;
+
+            no_escaping
+        }
+        Some(Markup::None) | None => no_escaping,
+    };
+
+    let (full_text, short_text) = match &block_config.kind {
+        BlockKind::Battery(battery_style) => {
+            match &data.battery().0 {
+                Ok(Some((_timestamp, status))) => {
+                    if let Some(warning_color) = battery_style.color(status) {
+                        color = Some(warning_color.to_string());
+                    }
+                    urgent = battery_style.urgent(status).then_some(true);
+                    percentage = Some(status.percentage.clamp(0.0, 100.0).round() as u8);
+
+                    classes.push(status.state.class().to_string());
+                    if let Some(class) = battery_style.class(status) {
+                        classes.push(class.to_string());
+                    }
+                }
+                Ok(None) => {}
+                Err(_) => classes.push("error".to_string()),
+            }
+
+            let formatted = |format| match &data.battery().0 {
+                Ok(Some((_timestamp, status))) => {
+                    FormattedBatteryStatus(*status, format, escape).to_string()
+                }
+                Ok(None) => "None".to_string(),
+                Err(e) => escape(&e.to_string()).into_owned(),
+            };
+
+            (
+                formatted(&block_config.format),
+                formatted(&block_config.short_format),
+            )
+        }
+        BlockKind::Network => {
+            let network = data.network();
+
+            match &network.0 {
+                Ok(Some((_timestamp, status))) => {
+                    percentage = status
+                        .access_point
+                        .as_ref()
+                        .map(|access_point| access_point.strength.min(100));
+
+                    classes.push(status.kind.class().to_string());
+                    if let Some(problem) = status.connectivity.problem() {
+                        classes.push(problem.to_string());
+                    }
+                }
+                Ok(None) => {}
+                Err(_) => classes.push("error".to_string()),
+            }
+
+            let formatted = |format| match &network.0 {
+                Ok(Some((_timestamp, status))) => {
+                    FormattedNetworkStatus(status, format, escape).to_string()
+                }
+                Ok(None) => "None".to_string(),
+                Err(e) => escape(&e.to_string()).into_owned(),
+            };
+
+            (
+                formatted(&block_config.format),
+                formatted(&block_config.short_format),
+            )
+        }
+        BlockKind::Clock => {
+            let time = data.time();
+
+            if time.0.is_err() {
+                classes.push("error".to_string());
+            }
+
+            let format = if data.short_clock() {
+                &block_config.short_format
+            } else {
+                &block_config.format
+            };
+            let mut clock = FormattedDTD(&time, format, escape).to_string();
+
+            // Hint that the timezone might have changed without us noticing.
+            if data.timezone_stale() {
+                clock.push('?');
+            }
+
+            (
+                clock,
+                FormattedDTD(&time, &block_config.short_format, escape).to_string(),
+            )
+        }
+    };
+
+    Block {
+        name: Some(block_config.kind.name().to_string()),
+        short_text: Some(short_text),
+        color,
+        background: block_config.background.clone(),
+        border: block_config.border.clone(),
+        min_width: block_config.min_width.clone(),
+        align: block_config.align,
+        urgent,
+        separator: block_config.separator,
+        separator_block_width: block_config.separator_block_width,
+        markup,
+        percentage,
+        classes,
+        ..Block::new(full_text)
+    }
+}
+
+pub fn status_blocks(data: &StatusbarData, config: &Config, renderer: &dyn Renderer) -> Vec<Block> {
+    // Make sure a broken config gets noticed, even though we're still
+    // going with the last one that worked.
+    let config_problem = data.config_problem().map(|problem| Block {
+        name: Some("config".to_string()),
+        urgent: Some(true),
+        classes: vec!["error".to_string()],
+        ..Block::new(problem.to_string())
+    });
+
+    config_problem
+        .into_iter()
+        .chain(
+            config
+                .blocks
+                .iter()
+                .map(|block_config| status_block(data, block_config, renderer.understands_pango())),
+        )
+        .collect()
+}
+
+async fn print_status_line(
+    data: &StatusbarData,
+    config: &Config,
+    renderer: &dyn Renderer,
+    io_ctx: &Rc<Mutex<StatusbarIOContext<'_>>>,
+) -> Result<(), Box<dyn Error>> {
+    let line = renderer.status_line(&status_blocks(data, config, renderer));
+
+    let output = &mut io_ctx.lock().await.statusbar_output;
+
+    output.write_all(line.as_bytes()).await?;
+    output.flush().await?;
+
+    Ok(())
+}
+
+fn handle_click(data: &mut StatusbarData, click: &ClickEvent) {
+    if let (Some("clock"), BUTTON_LEFT) = (click.name.as_deref(), click.button) {
+        data.toggle_short_clock();
+    }
+}
+
+async fn print_infinite_body(
+    mut change_q: Receiver<StatusbarChangeCause>,
+    io_ctx: Rc<Mutex<StatusbarIOContext<'_>>>,
+    mut config: Config,
+    renderer: &dyn Renderer,
+) -> Result<(), Box<dyn Error>> {
+    let mut data = StatusbarData::new();
+    let mut buf = Vec::with_capacity(4);
+
+    // Whether the bar has asked us to hold off on output. We keep
+    // track of changes in the meantime, so we can print a fresh line
+    // as soon as we're continued.
+    let mut stopped = false;
+
+    loop {
+        if !stopped {
+            print_status_line(&data, &config, renderer, &io_ctx).await?;
+        }
+
+        loop {
+            match change_q.recv_many(&mut buf, 128).await {
+                0 => {
+                    // No more, Senders must be shut down. I guess it's time
+                    // to close up.
+                    return Ok(());
+                }
+                _ => {
+                    // Process a bunch of our messages before rerendering.
+                    for msg in buf.drain(..) {
+                        match msg {
+                            TzChange(tz_change) => {
+                                data.update_timezone_maybedata(tz_change);
+                            }
+                            BatteryChange(bat_change) => {
+                                data.update_battery_maybedata(bat_change);
+                            }
+                            NetworkChange(network_change) => {
+                                data.update_network_maybedata(network_change);
+                            }
+                            SystemBusLost {
+                                problem,
+                                timezone_too,
+                            } => {
+                                data.mark_system_bus_lost(problem, timezone_too);
+                            }
+                            Click(click) => {
+                                handle_click(&mut data, &click);
+                            }
+                            ConfigReload(Ok(new_config)) => {
+                                config = new_config;
+                                data.set_config_problem(None);
+                            }
+                            ConfigReload(Err(problem)) => {
+                                data.set_config_problem(Some(problem));
+                            }
+                            Stop => {
+                                stopped = true;
+                            }
+                            Cont => {
+                                stopped = false;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+
+            // See if there's more messages that we should process
+            // before printing out the new status line.
+            if change_q.is_empty() {
+                break;
+            }
+        }
+    }
+}
+
+pub async fn run_statusbar_updater(
+    change_q: Receiver<StatusbarChangeCause>,
+    io_ctx: Rc<Mutex<StatusbarIOContext<'_>>>,
+    config: Config,
+    renderer: Box<dyn Renderer>,
+) -> Result<(), Box<dyn Error>> {
+    let header = renderer.header();
+    if !header.is_empty() {
+        let output = &mut io_ctx.lock().await.statusbar_output;
+        output.write_all(header.as_bytes()).await?;
+    }
+
+    print_infinite_body(change_q, io_ctx, config, renderer.as_ref()).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod lemonbartests;
+#[cfg(test)]
+mod rendertests;
+#[cfg(test)]
+mod texttests;
+#[cfg(test)]
+mod waybartests;
@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::time::Instant;
+
+use crate::config::{BlockConfig, Config};
+use crate::data::network::{ConnectionKind, NetworkStatus};
+use crate::data::{MaybeData, StatusbarData};
+use crate::render::lemonbar::LemonbarRenderer;
+use crate::render::text::{PlainTextRenderer, TmuxRenderer};
+use crate::render::{Renderer, status_blocks};
+use crate::swaybar::{EscapePolicy, Markup, SwaybarRenderer};
+
+#[cfg(test)]
+fn pango_network() -> (StatusbarData, Config) {
+    let mut data = StatusbarData::new();
+    data.update_network_maybedata(MaybeData(Ok(Some((
+        Instant::now(),
+        NetworkStatus {
+            kind: ConnectionKind::Wifi,
+            name: "<&>".to_string(),
+            ..Default::default()
+        },
+    )))));
+
+    let config = Config {
+        blocks: vec![BlockConfig {
+            format: "<b>%n</b> &amp; %k".to_string(),
+            short_format: "<i>%k</i>".to_string(),
+            markup: Some(Markup::Pango),
+            min_width: None,
+            ..BlockConfig::network()
+        }],
+    };
+
+    (data, config)
+}
+
+#[test]
+fn check_pango_for_pango_outputs() {
+    let (data, config) = pango_network();
+    let renderer = SwaybarRenderer {
+        policy: EscapePolicy::MinimalEscaping(),
+    };
+
+    let blocks = status_blocks(&data, &config, &renderer);
+    assert_eq!(blocks[0].full_text, "<b>&lt;&amp;&gt;</b> &amp; wifi");
+    assert_eq!(blocks[0].short_text.as_deref(), Some("<i>wifi</i>"));
+    assert_eq!(blocks[0].markup, Some(Markup::Pango));
+}
+
+#[test]
+fn check_pango_stripped_for_plain_outputs() {
+    let (data, config) = pango_network();
+
+    let line =
+        |renderer: &dyn Renderer| renderer.status_line(&status_blocks(&data, &config, renderer));
+
+    assert_eq!(line(&PlainTextRenderer), "<&> & wifi\n");
+    assert_eq!(line(&TmuxRenderer), "<&> & wifi\n");
+    assert_eq!(line(&LemonbarRenderer), "%{A:network:}<&> & wifi%{A}\n");
+}
@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::render::Renderer;
+use crate::swaybar::Block;
+
+/**
+ * What goes between blocks, like `StatusbarData`'s `Display` does it.
+ */
+const SEPARATOR: &str = " | ";
+
+/**
+ * Put text on a single line, since whatever reads our output takes
+ * each line as a whole status line. Error messages from D-Bus and the
+ * like can have line breaks in them.
+ */
+pub fn single_line(text: &str) -> String {
+    text.replace(['\n', '\r'], " ")
+}
+
+/**
+ * Plain lines of text, for anything that just reads lines, e.g.
+ * `xsetroot -name` for dwm.
+ */
+pub struct PlainTextRenderer;
+
+impl Renderer for PlainTextRenderer {
+    fn status_line(&self, blocks: &[Block]) -> String {
+        let mut line = blocks
+            .iter()
+            .map(|block| single_line(&block.full_text))
+            .collect::<Vec<_>>()
+            .join(SEPARATOR);
+        line.push('\n');
+
+        line
+    }
+}
+
+/**
+ * Lines of text with tmux style tags, for `status-right` and friends.
+ */
+pub struct TmuxRenderer;
+
+/**
+ * Escape text so tmux doesn't take it as a format.
+ */
+fn escape_tmux(text: &str) -> String {
+    text.replace('#', "##")
+}
+
+/**
+ * Our colors can have alpha at the end, which tmux doesn't do.
+ */
+fn tmux_color(color: &str) -> &str {
+    color.get(..7).unwrap_or(color)
+}
+
+impl Renderer for TmuxRenderer {
+    fn status_line(&self, blocks: &[Block]) -> String {
+        let mut line = blocks
+            .iter()
+            .map(|block| {
+                let mut style = Vec::new();
+                if let Some(color) = &block.color {
+                    style.push(format!("fg={}", tmux_color(color)));
+                }
+                if let Some(background) = &block.background {
+                    style.push(format!("bg={}", tmux_color(background)));
+                }
+                if block.urgent == Some(true) {
+                    style.push("reverse".to_string());
+                }
+
+                if style.is_empty() {
+                    escape_tmux(&single_line(&block.full_text))
+                } else {
+                    format!(
+                        "#[{}]{}#[default]",
+                        style.join(","),
+                        escape_tmux(&single_line(&block.full_text))
+                    )
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(SEPARATOR);
+        line.push('\n');
+
+        line
+    }
+}
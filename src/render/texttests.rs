@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::render::Renderer;
+use crate::render::text::*;
+use crate::swaybar::Block;
+
+#[cfg(test)]
+fn some_blocks() -> Vec<Block> {
+    vec![
+        Block {
+            color: Some("#FF0000".to_string()),
+            urgent: Some(true),
+            ..Block::new(" 5%-".to_string())
+        },
+        Block::new("#1 12:34".to_string()),
+    ]
+}
+
+#[test]
+fn check_plain_text() {
+    assert_eq!(
+        PlainTextRenderer.status_line(&some_blocks()),
+        " 5%- | #1 12:34\n"
+    );
+}
+
+#[test]
+fn check_tmux() {
+    assert_eq!(
+        TmuxRenderer.status_line(&some_blocks()),
+        "#[fg=#FF0000,reverse] 5%-#[default] | ##1 12:34\n"
+    );
+}
+
+#[test]
+fn check_text_stays_on_one_line() {
+    let blocks = vec![
+        Block::new("Can't connect:\nno such file\r\n".to_string()),
+        Block::new("#1\n12:34".to_string()),
+    ];
+
+    assert_eq!(
+        PlainTextRenderer.status_line(&blocks),
+        "Can't connect: no such file   | #1 12:34\n"
+    );
+    assert_eq!(
+        TmuxRenderer.status_line(&blocks),
+        "Can't connect: no such file   | ##1 12:34\n"
+    );
+}
+
+#[test]
+fn check_tmux_drops_alpha() {
+    let blocks = vec![Block {
+        color: Some("#FF000080".to_string()),
+        background: Some("#00000000".to_string()),
+        ..Block::new("50%".to_string())
+    }];
+
+    assert_eq!(
+        TmuxRenderer.status_line(&blocks),
+        "#[fg=#FF0000,bg=#000000]50%#[default]\n"
+    );
+}
@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::render::Renderer;
+use crate::swaybar::Block;
+use crate::swaybar::{EscapeJSONString, EscapePolicy};
+
+/**
+ * One block as a waybar custom module with `return-type: json`, i.e. a
+ * JSON object on each line. We print a new one whenever something
+ * changes, so waybar doesn't need to poll us.
+ */
+pub struct WaybarRenderer {
+    /**
+     * The name of the block to show.
+     */
+    pub block: String,
+    pub policy: EscapePolicy,
+}
+
+fn push_json_string(out: &mut String, value: &str, policy: EscapePolicy) {
+    out.push('"');
+    out.extend(EscapeJSONString::new_from_str(value, policy));
+    out.push('"');
+}
+
+impl Renderer for WaybarRenderer {
+    fn status_line(&self, blocks: &[Block]) -> String {
+        // We only get to show one block, so a broken config takes it
+        // over. Otherwise nobody would notice.
+        let named = |name: &str| {
+            blocks
+                .iter()
+                .find(|block| block.name.as_deref() == Some(name))
+        };
+
+        let Some(block) = named("config").or_else(|| named(&self.block)) else {
+            // The block isn't in the config (anymore), so hide the module.
+            return "{\"text\": \"\"}\n".to_string();
+        };
+
+        let mut line = String::from("{\"text\": ");
+        push_json_string(&mut line, &block.full_text, self.policy);
+
+        line.push_str(", \"alt\": ");
+        push_json_string(
+            &mut line,
+            block.short_text.as_deref().unwrap_or(&block.full_text),
+            self.policy,
+        );
+
+        line.push_str(", \"tooltip\": ");
+        push_json_string(&mut line, &block.full_text, self.policy);
+
+        line.push_str(", \"class\": [");
+        let urgent = (block.urgent == Some(true)).then_some("urgent");
+        for (i, class) in block
+            .classes
+            .iter()
+            .map(String::as_str)
+            .chain(urgent)
+            .enumerate()
+        {
+            if i != 0 {
+                line.push_str(", ");
+            }
+            push_json_string(&mut line, class, self.policy);
+        }
+        line.push(']');
+
+        if let Some(percentage) = block.percentage {
+            line.push_str(&format!(", \"percentage\": {}", percentage));
+        }
+
+        line.push_str("}\n");
+
+        line
+    }
+
+    /**
+     * waybar shows a custom module's text as Pango markup.
+     */
+    fn understands_pango(&self) -> bool {
+        true
+    }
+}
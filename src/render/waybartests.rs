@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::render::Renderer;
+use crate::render::waybar::*;
+use crate::swaybar::Block;
+use crate::swaybar::EscapePolicy::MinimalEscaping;
+
+#[test]
+fn check_waybar_block() {
+    let blocks = vec![
+        Block {
+            name: Some("battery".to_string()),
+            short_text: Some(" 5%-".to_string()),
+            urgent: Some(true),
+            percentage: Some(5),
+            classes: vec!["discharging".to_string(), "critical".to_string()],
+            ..Block::new(" 5%- 0:10".to_string())
+        },
+        Block {
+            name: Some("clock".to_string()),
+            ..Block::new("\"noon\"".to_string())
+        },
+    ];
+
+    let battery = WaybarRenderer {
+        block: "battery".to_string(),
+        policy: MinimalEscaping(),
+    };
+    assert_eq!(
+        battery.status_line(&blocks),
+        "{\"text\": \" 5%- 0:10\", \"alt\": \" 5%-\", \"tooltip\": \" 5%- 0:10\", \
+         \"class\": [\"discharging\", \"critical\", \"urgent\"], \"percentage\": 5}\n"
+    );
+
+    let clock = WaybarRenderer {
+        block: "clock".to_string(),
+        policy: MinimalEscaping(),
+    };
+    assert_eq!(
+        clock.status_line(&blocks),
+        "{\"text\": \"\\\"noon\\\"\", \"alt\": \"\\\"noon\\\"\", \
+         \"tooltip\": \"\\\"noon\\\"\", \"class\": []}\n"
+    );
+
+    let missing = WaybarRenderer {
+        block: "weather".to_string(),
+        policy: MinimalEscaping(),
+    };
+    assert_eq!(missing.status_line(&blocks), "{\"text\": \"\"}\n");
+}
+
+#[test]
+fn check_waybar_config_problem() {
+    let blocks = vec![
+        Block {
+            name: Some("config".to_string()),
+            urgent: Some(true),
+            classes: vec!["error".to_string()],
+            ..Block::new("config line 2: bad color".to_string())
+        },
+        Block {
+            name: Some("clock".to_string()),
+            ..Block::new("12:34".to_string())
+        },
+    ];
+
+    let clock = WaybarRenderer {
+        block: "clock".to_string(),
+        policy: MinimalEscaping(),
+    };
+    assert_eq!(
+        clock.status_line(&blocks),
+        "{\"text\": \"config line 2: bad color\", \"alt\": \"config line 2: bad color\", \
+         \"tooltip\": \"config line 2: bad color\", \"class\": [\"error\", \"urgent\"]}\n"
+    );
+}
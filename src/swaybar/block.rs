@@ -0,0 +1,220 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::swaybar::json::{EscapeJSONString, EscapePolicy};
+
+/**
+ * How to align the text in a block that's narrower than its min_width.
+ */
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+impl Align {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Left => "left",
+            Self::Center => "center",
+            Self::Right => "right",
+        }
+    }
+}
+
+/**
+ * How the bar should interpret the text in a block.
+ */
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Markup {
+    None,
+    Pango,
+}
+
+impl Markup {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Pango => "pango",
+        }
+    }
+}
+
+/**
+ * The minimum width of a block, either in pixels or as wide as some
+ * sample text.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MinWidth {
+    Pixels(u32),
+    Text(String),
+}
+
+/**
+ * One block of the status line, covering the whole i3bar block schema.
+ * Anything that's `None` is left out, so the bar uses its default.
+ */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Block {
+    pub full_text: String,
+    pub short_text: Option<String>,
+    pub color: Option<String>,
+    pub background: Option<String>,
+    pub border: Option<String>,
+    pub border_top: Option<u32>,
+    pub border_bottom: Option<u32>,
+    pub border_left: Option<u32>,
+    pub border_right: Option<u32>,
+    pub min_width: Option<MinWidth>,
+    pub align: Option<Align>,
+    pub urgent: Option<bool>,
+    pub name: Option<String>,
+    pub instance: Option<String>,
+    pub separator: Option<bool>,
+    pub separator_block_width: Option<u32>,
+    pub markup: Option<Markup>,
+    /**
+     * These aren't part of the i3bar schema, so they never get written
+     * out as JSON. They're for outputs that know what to do with them,
+     * like waybar.
+     */
+    pub percentage: Option<u8>,
+    pub classes: Vec<String>,
+}
+
+/**
+ * Writes out the members of a JSON object, one per line, taking care
+ * of the commas between them.
+ */
+struct JSONMemberWriter<'a> {
+    out: &'a mut String,
+    indent: &'a str,
+    policy: EscapePolicy,
+    first: bool,
+}
+
+impl JSONMemberWriter<'_> {
+    fn key(&mut self, key: &str) {
+        if !self.first {
+            self.out.push_str(",\n");
+        }
+        self.first = false;
+
+        // Our keys are all hardcoded, and none of them need escaping.
+        self.out.push_str(self.indent);
+        self.out.push('"');
+        self.out.push_str(key);
+        self.out.push_str("\": ");
+    }
+
+    fn string(&mut self, key: &str, value: &str) {
+        self.key(key);
+        self.out.push('"');
+        self.out
+            .extend(EscapeJSONString::new_from_str(value, self.policy));
+        self.out.push('"');
+    }
+
+    fn number(&mut self, key: &str, value: u32) {
+        self.key(key);
+        self.out.push_str(&value.to_string());
+    }
+
+    fn boolean(&mut self, key: &str, value: bool) {
+        self.key(key);
+        self.out.push_str(if value { "true" } else { "false" });
+    }
+
+    fn opt_string(&mut self, key: &str, value: &Option<String>) {
+        if let Some(value) = value {
+            self.string(key, value);
+        }
+    }
+
+    fn opt_number(&mut self, key: &str, value: Option<u32>) {
+        if let Some(value) = value {
+            self.number(key, value);
+        }
+    }
+
+    fn opt_boolean(&mut self, key: &str, value: Option<bool>) {
+        if let Some(value) = value {
+            self.boolean(key, value);
+        }
+    }
+}
+
+impl Block {
+    pub fn new(full_text: String) -> Self {
+        Block {
+            full_text,
+            ..Default::default()
+        }
+    }
+
+    /**
+     * Append this block as a JSON object to `out`. The members are
+     * indented by `indent` plus two spaces, and there's no trailing
+     * newline.
+     */
+    pub fn write_json(&self, out: &mut String, indent: &str, policy: EscapePolicy) {
+        let member_indent = format!("{}  ", indent);
+
+        out.push_str(indent);
+        out.push_str("{\n");
+
+        let mut members = JSONMemberWriter {
+            out,
+            indent: &member_indent,
+            policy,
+            first: true,
+        };
+
+        members.opt_string("name", &self.name);
+        members.opt_string("instance", &self.instance);
+        members.string("full_text", &self.full_text);
+        members.opt_string("short_text", &self.short_text);
+        members.opt_string("color", &self.color);
+        members.opt_string("background", &self.background);
+        members.opt_string("border", &self.border);
+        members.opt_number("border_top", self.border_top);
+        members.opt_number("border_bottom", self.border_bottom);
+        members.opt_number("border_left", self.border_left);
+        members.opt_number("border_right", self.border_right);
+        match &self.min_width {
+            Some(MinWidth::Pixels(px)) => members.number("min_width", *px),
+            Some(MinWidth::Text(sample)) => members.string("min_width", sample),
+            None => {}
+        }
+        if let Some(align) = self.align {
+            members.string("align", align.as_str());
+        }
+        members.opt_boolean("urgent", self.urgent);
+        members.opt_boolean("separator", self.separator);
+        members.opt_number("separator_block_width", self.separator_block_width);
+        if let Some(markup) = self.markup {
+            members.string("markup", markup.as_str());
+        }
+
+        out.push('\n');
+        out.push_str(indent);
+        out.push('}');
+    }
+}
@@ -0,0 +1,97 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::swaybar::block::*;
+use crate::swaybar::json::EscapePolicy::*;
+
+#[cfg(test)]
+fn block_json(block: &Block) -> String {
+    let mut out = String::new();
+    block.write_json(&mut out, "", MinimalEscaping());
+    out
+}
+
+#[test]
+fn check_minimal_block() {
+    assert_eq!(
+        block_json(&Block::new("some text".to_string())),
+        "{\n  \"full_text\": \"some text\"\n}"
+    );
+}
+
+#[test]
+fn check_block_escapes_text() {
+    let block = Block {
+        name: Some("quo\"te".to_string()),
+        ..Block::new("back\\slash".to_string())
+    };
+
+    assert_eq!(
+        block_json(&block),
+        "{\n  \"name\": \"quo\\\"te\",\n  \"full_text\": \"back\\\\slash\"\n}"
+    );
+}
+
+#[test]
+fn check_full_block() {
+    let block = Block {
+        full_text: "full".to_string(),
+        short_text: Some("short".to_string()),
+        color: Some("#FF0000".to_string()),
+        background: Some("#000000".to_string()),
+        border: Some("#FFFFFF".to_string()),
+        border_top: Some(1),
+        border_bottom: Some(2),
+        border_left: Some(3),
+        border_right: Some(4),
+        min_width: Some(MinWidth::Pixels(100)),
+        align: Some(Align::Center),
+        urgent: Some(true),
+        name: Some("name".to_string()),
+        instance: Some("instance".to_string()),
+        separator: Some(false),
+        separator_block_width: Some(9),
+        markup: Some(Markup::Pango),
+        percentage: Some(50),
+        classes: vec!["class".to_string()],
+    };
+
+    assert_eq!(
+        block_json(&block),
+        "{\n\
+        \x20 \"name\": \"name\",\n\
+        \x20 \"instance\": \"instance\",\n\
+        \x20 \"full_text\": \"full\",\n\
+        \x20 \"short_text\": \"short\",\n\
+        \x20 \"color\": \"#FF0000\",\n\
+        \x20 \"background\": \"#000000\",\n\
+        \x20 \"border\": \"#FFFFFF\",\n\
+        \x20 \"border_top\": 1,\n\
+        \x20 \"border_bottom\": 2,\n\
+        \x20 \"border_left\": 3,\n\
+        \x20 \"border_right\": 4,\n\
+        \x20 \"min_width\": 100,\n\
+        \x20 \"align\": \"center\",\n\
+        \x20 \"urgent\": true,\n\
+        \x20 \"separator\": false,\n\
+        \x20 \"separator_block_width\": 9,\n\
+        \x20 \"markup\": \"pango\"\n\
+        }"
+    );
+}
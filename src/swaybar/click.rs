@@ -0,0 +1,305 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader};
+use tokio::sync::Mutex;
+use tokio::sync::mpsc::Sender;
+
+use crate::data::StatusbarChangeCause;
+use crate::data::click::ClickEvent;
+use crate::io::StatusbarIOContext;
+use crate::swaybar::json::UnescapeJSONString;
+
+/**
+ * Something went wrong while parsing a click event.
+ */
+#[derive(Clone, Debug)]
+pub struct ClickParseError {
+    /**
+     * The byte offset into the event where we noticed the problem.
+     */
+    pub position: usize,
+    pub what: &'static str,
+}
+
+impl fmt::Display for ClickParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at byte {}", self.what, self.position)
+    }
+}
+
+impl Error for ClickParseError {}
+
+/**
+ * Just enough of a JSON value to pick click events apart.
+ */
+#[derive(Clone, Debug, PartialEq)]
+enum JSONValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JSONValue>),
+    Object(Vec<(String, JSONValue)>),
+}
+
+struct JSONParser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> JSONParser<'a> {
+    fn new(input: &'a str) -> Self {
+        JSONParser { input, position: 0 }
+    }
+
+    fn error(&self, what: &'static str) -> ClickParseError {
+        ClickParseError {
+            position: self.position,
+            what,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(' ' | '\t' | '\n' | '\r') = self.peek() {
+            self.bump();
+        }
+    }
+
+    fn expect(&mut self, expected: char, what: &'static str) -> Result<(), ClickParseError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.bump();
+                Ok(())
+            }
+            _ => Err(self.error(what)),
+        }
+    }
+
+    fn expect_literal(&mut self, literal: &str) -> Result<(), ClickParseError> {
+        if self.input[self.position..].starts_with(literal) {
+            self.position += literal.len();
+            Ok(())
+        } else {
+            Err(self.error("unknown literal"))
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<JSONValue, ClickParseError> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some('{') => self.parse_object(),
+            Some('[') => self.parse_array(),
+            Some('"') => Ok(JSONValue::String(self.parse_string()?)),
+            Some('-' | '0'..='9') => self.parse_number(),
+            Some('t') => self.expect_literal("true").map(|_| JSONValue::Bool(true)),
+            Some('f') => self.expect_literal("false").map(|_| JSONValue::Bool(false)),
+            Some('n') => self.expect_literal("null").map(|_| JSONValue::Null),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<JSONValue, ClickParseError> {
+        self.expect('{', "expected '{'")?;
+
+        let mut members = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.bump();
+            return Ok(JSONValue::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.skip_whitespace();
+            self.expect(':', "expected ':'")?;
+            let value = self.parse_value()?;
+            members.push((key, value));
+
+            self.skip_whitespace();
+            match self.bump() {
+                Some(',') => continue,
+                Some('}') => return Ok(JSONValue::Object(members)),
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<JSONValue, ClickParseError> {
+        self.expect('[', "expected '['")?;
+
+        let mut elements = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.bump();
+            return Ok(JSONValue::Array(elements));
+        }
+
+        loop {
+            elements.push(self.parse_value()?);
+
+            self.skip_whitespace();
+            match self.bump() {
+                Some(',') => continue,
+                Some(']') => return Ok(JSONValue::Array(elements)),
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<JSONValue, ClickParseError> {
+        let start = self.position;
+
+        while let Some('-' | '+' | '.' | 'e' | 'E' | '0'..='9') = self.peek() {
+            self.bump();
+        }
+
+        self.input[start..self.position]
+            .parse::<f64>()
+            .map(JSONValue::Number)
+            .map_err(|_| ClickParseError {
+                position: start,
+                what: "invalid number",
+            })
+    }
+
+    fn parse_string(&mut self) -> Result<String, ClickParseError> {
+        self.expect('"', "expected '\"'")?;
+
+        // Find the closing quotation mark first, skipping over whatever
+        // is escaped, and then unescape everything before it.
+        let start = self.position;
+        loop {
+            match self.bump() {
+                None => return Err(self.error("unterminated string")),
+                Some('"') => break,
+                Some('\\') => {
+                    self.bump();
+                }
+                Some(_) => {}
+            }
+        }
+
+        UnescapeJSONString::new_from_str(&self.input[start..self.position - 1])
+            .collect::<Result<String, _>>()
+            .map_err(|e| ClickParseError {
+                position: start + e.position,
+                what: e.what,
+            })
+    }
+}
+
+pub fn parse_click_event(event: &str) -> Result<ClickEvent, ClickParseError> {
+    let mut parser = JSONParser::new(event);
+
+    let members = match parser.parse_value()? {
+        JSONValue::Object(members) => members,
+        _ => {
+            return Err(ClickParseError {
+                position: 0,
+                what: "click event is not an object",
+            });
+        }
+    };
+
+    parser.skip_whitespace();
+    if parser.peek().is_some() {
+        return Err(parser.error("trailing characters after click event"));
+    }
+
+    let mut click = ClickEvent::default();
+
+    // Unknown members (and members of the wrong type) are ignored, since
+    // bars like to add their own extensions.
+    for (key, value) in members {
+        match (key.as_str(), value) {
+            ("name", JSONValue::String(name)) => click.name = Some(name),
+            ("instance", JSONValue::String(instance)) => click.instance = Some(instance),
+            ("button", JSONValue::Number(button)) => click.button = button as u32,
+            ("x", JSONValue::Number(x)) => click.x = x as i64,
+            ("y", JSONValue::Number(y)) => click.y = y as i64,
+            ("modifiers", JSONValue::Array(modifiers)) => {
+                click.modifiers = modifiers
+                    .into_iter()
+                    .filter_map(|modifier| match modifier {
+                        JSONValue::String(m) => Some(m),
+                        _ => None,
+                    })
+                    .collect()
+            }
+            _ => {}
+        }
+    }
+
+    Ok(click)
+}
+
+/**
+ * Read the infinite array of click events that the bar sends us, and
+ * pass them along as they come in.
+ */
+pub async fn read_click_events(
+    click_input: Box<dyn AsyncRead + Unpin>,
+    change_q: Sender<StatusbarChangeCause>,
+    io_ctx: Rc<Mutex<StatusbarIOContext<'_>>>,
+) -> Result<(), Box<dyn Error>> {
+    let mut lines = BufReader::new(click_input).lines();
+
+    // The bar puts each event on its own line, but the opening bracket
+    // and the commas between events end up at the start of lines.
+    while let Some(line) = lines.next_line().await? {
+        let event = line.trim().trim_start_matches(['[', ',']).trim_start();
+
+        if event.is_empty() {
+            continue;
+        }
+
+        match parse_click_event(event) {
+            Ok(click) => change_q.send(StatusbarChangeCause::Click(click)).await?,
+            Err(e) => {
+                let output = &mut io_ctx.lock().await.debug_output;
+                output
+                    .write_all(format!("Ignoring bad click event: {}\n", e).as_bytes())
+                    .await?;
+                output.flush().await?;
+            }
+        }
+    }
+
+    // The bar closed our input, so there won't be any more clicks.
+    Ok(())
+}
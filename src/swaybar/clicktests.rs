@@ -0,0 +1,143 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::io::Cursor;
+use std::rc::Rc;
+use tokio::io::{AsyncWrite, sink};
+use tokio::runtime::Builder;
+use tokio::sync::Mutex;
+use tokio::sync::mpsc::channel;
+
+use crate::data::StatusbarChangeCause;
+use crate::data::click::ClickEvent;
+use crate::io::StatusbarIOContext;
+use crate::swaybar::click::*;
+
+#[cfg(test)]
+fn error_at(event: &str) -> (usize, &'static str) {
+    let e = parse_click_event(event).unwrap_err();
+    (e.position, e.what)
+}
+
+#[test]
+fn check_click_members() {
+    let click = parse_click_event(
+        r#"{"name": "clock", "instance": "0", "button": 3, "x": 1280, "y": -4, "modifiers": ["Shift", "Mod4"]}"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        click,
+        ClickEvent {
+            name: Some("clock".to_string()),
+            instance: Some("0".to_string()),
+            button: 3,
+            x: 1280,
+            y: -4,
+            modifiers: vec!["Shift".to_string(), "Mod4".to_string()],
+        }
+    );
+}
+
+#[test]
+fn check_click_skips_unknown_members() {
+    let click = parse_click_event(
+        r#"{"relative_x": 12.5, "extra": {"nested": [1, {"deeper": null}], "flag": true},
+            "list": [[], {}, "str", false], "name": "battery", "button": 1,
+            "instance": 7, "modifiers": ["Control", 2, null]}"#,
+    )
+    .unwrap();
+
+    assert_eq!(click.name.as_deref(), Some("battery"));
+    assert_eq!(click.button, 1);
+    // Members of the wrong type are ignored too.
+    assert_eq!(click.instance, None);
+    assert_eq!(click.modifiers, vec!["Control".to_string()]);
+}
+
+#[test]
+fn check_click_escaped_strings() {
+    let click = parse_click_event(r#"{"name": "quo\"te \\ é😀\n", "instance": "\/"}"#).unwrap();
+
+    assert_eq!(click.name.as_deref(), Some("quo\"te \\ \u{e9}\u{1f600}\n"));
+    assert_eq!(click.instance.as_deref(), Some("/"));
+}
+
+#[test]
+fn check_bad_click_events() {
+    assert_eq!(error_at(r#"{"button": 1,}"#), (13, "expected '\"'"));
+    assert_eq!(error_at(r#"{"button": 1"#), (12, "expected ',' or '}'"));
+    assert_eq!(error_at(r#"{"button" 1}"#), (10, "expected ':'"));
+    assert_eq!(
+        error_at(r#"{"button": 1} x"#),
+        (14, "trailing characters after click event")
+    );
+    assert_eq!(error_at(r#"{"name": "abc"#), (13, "unterminated string"));
+    assert_eq!(error_at(r#"{"name": "a\qb"}"#), (11, "invalid escape"));
+    assert_eq!(error_at(r#"{"name": "\ud83d"}"#), (10, "lone surrogate"));
+    assert_eq!(error_at(r#"{"x": 1.2.3}"#), (6, "invalid number"));
+    assert_eq!(error_at(r#"{"urgent": yes}"#), (11, "unexpected character"));
+    assert_eq!(error_at(r#"{"urgent": nope}"#), (11, "unknown literal"));
+    assert_eq!(error_at(r#"[1, 2]"#), (0, "click event is not an object"));
+    assert_eq!(error_at(""), (0, "unexpected end of input"));
+}
+
+#[test]
+fn check_click_event_framing() {
+    // The bar opens the infinite array on its own line, and puts the
+    // commas at the start of the following events.
+    let input = "[\n\
+        {\"name\": \"clock\", \"button\": 1}\n\
+        ,{\"name\": \"battery\", \"button\": 2}\n\
+        \n\
+        , {\"name\": \"broken\"\n\
+        ,{\"name\": \"network\", \"button\": 3}\n";
+
+    let (tx, mut rx) = channel(8);
+    let output: Box<dyn AsyncWrite + Unpin + Send + Sync> = Box::new(sink());
+    let io_ctx = Rc::new(Mutex::new(StatusbarIOContext::from(output)));
+
+    Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(read_click_events(
+            Box::new(Cursor::new(input.as_bytes().to_vec())),
+            tx,
+            io_ctx,
+        ))
+        .unwrap();
+
+    let mut clicks = Vec::new();
+    while let Ok(change) = rx.try_recv() {
+        match change {
+            StatusbarChangeCause::Click(click) => clicks.push((click.name.unwrap(), click.button)),
+            _ => panic!("expected only clicks"),
+        }
+    }
+
+    // The broken event gets skipped, and the rest still come through.
+    assert_eq!(
+        clicks,
+        vec![
+            ("clock".to_string(), 1),
+            ("battery".to_string(), 2),
+            ("network".to_string(), 3),
+        ]
+    );
+}
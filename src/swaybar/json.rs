@@ -105,6 +105,18 @@ pub enum EscapePolicy {
      * characters are requested to be escaped.
      */
     MinimalEscaping(),
+    /**
+     * Escape like `MinimalEscaping`, and also escape every code point
+     * that isn't ASCII, for consumers that choke on raw UTF-8.
+     */
+    AsciiOnly(),
+    /**
+     * Escape like `MinimalEscaping`, but use the short escapes for
+     * control characters that have them, and also escape solidus, line
+     * separator, and paragraph separator. This is safe to embed in
+     * JavaScript and HTML `<script>`s.
+     */
+    Conservative(),
 }
 
 impl EscapePolicy {
@@ -114,6 +126,24 @@ impl EscapePolicy {
     pub fn decide(&self, c: &char) -> EscapeJSONDecision {
         match self {
             Self::MinimalEscaping() => Self::decide_minimal(c),
+            Self::AsciiOnly() => Self::decide_ascii_only(c),
+            Self::Conservative() => Self::decide_conservative(c),
+        }
+    }
+
+    fn decide_ascii_only(c: &char) -> EscapeJSONDecision {
+        match c {
+            '\u{80}'.. => UnicodeEscape(),
+            _ => Self::decide_minimal(c),
+        }
+    }
+
+    fn decide_conservative(c: &char) -> EscapeJSONDecision {
+        match c {
+            '"' | '\\' | '/' => SelfEscape(),
+            '\x08' | '\x0c' | '\n' | '\r' | '\t' => SingleCharEscape(),
+            '\u{2028}' | '\u{2029}' => UnicodeEscape(),
+            _ => Self::decide_minimal(c),
         }
     }
 
@@ -126,7 +156,7 @@ impl EscapePolicy {
     }
 }
 
-use std::str::Chars;
+use std::str::{CharIndices, Chars};
 
 #[derive(Clone, Debug)]
 pub struct EscapeJSONString<'a> {
@@ -299,6 +329,175 @@ impl Iterator for EscapeJSONString<'_> {
     }
 }
 
+use std::error::Error;
+use std::fmt;
 use std::iter::FusedIterator;
 
 impl FusedIterator for EscapeJSONString<'_> {}
+
+fn rfc_single_char_unescape(c: char) -> Option<char> {
+    match c {
+        '"' => Some('"'),
+        '\\' => Some('\\'),
+        '/' => Some('/'),
+
+        'b' => Some('\x08'), // backspace
+        'f' => Some('\x0c'), // form feed
+        'n' => Some('\n'),   // line feed
+        'r' => Some('\r'),   // carriage return
+        't' => Some('\t'),   // tab
+
+        _ => None,
+    }
+}
+
+/**
+ * Something was wrong with an escaped JSON string.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnescapeJSONError {
+    /**
+     * The byte offset into the escaped string where the problem starts,
+     * e.g. the `\` of a bad escape sequence.
+     */
+    pub position: usize,
+    pub what: &'static str,
+}
+
+impl fmt::Display for UnescapeJSONError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at byte {}", self.what, self.position)
+    }
+}
+
+impl Error for UnescapeJSONError {}
+
+/**
+ * Turn the contents of a JSON string, without the surrounding quotation
+ * marks, back into the chars it stands for. This is the reverse of
+ * `EscapeJSONString`, whatever the `EscapePolicy`.
+ *
+ * After the first error, there's nothing more to read.
+ */
+#[derive(Clone, Debug)]
+pub struct UnescapeJSONString<'a> {
+    input: CharIndices<'a>,
+    finished: bool,
+}
+
+impl<'a> UnescapeJSONString<'a> {
+    pub fn new_from_str(s: &'a str) -> Self {
+        UnescapeJSONString {
+            input: s.char_indices(),
+            finished: false,
+        }
+    }
+
+    fn error(&mut self, position: usize, what: &'static str) -> UnescapeJSONError {
+        self.finished = true;
+        UnescapeJSONError { position, what }
+    }
+
+    /**
+     * Read the four hex digits of a `\uXXXX` escape.
+     */
+    fn hex4(&mut self) -> Result<u16, UnescapeJSONError> {
+        let mut value = 0;
+
+        for _ in 0..4 {
+            match self.input.next() {
+                Some((_, c)) if c.is_ascii_hexdigit() => {
+                    let digit = c.to_digit(16).expect("We just checked it's a hex digit.");
+                    value = (value << 4) | u16::try_from(digit).expect("Hex digits fit in a u16.");
+                }
+                Some((position, _)) => return Err(self.error(position, "expected hex digit")),
+                None => {
+                    let position = self.input.offset();
+                    return Err(self.error(position, "unterminated unicode escape"));
+                }
+            }
+        }
+
+        Ok(value)
+    }
+
+    /**
+     * Finish a `\uXXXX` escape that started at `start`, reading the
+     * second half of a surrogate pair if there is one.
+     */
+    fn unicode_escape(&mut self, start: usize) -> Result<char, UnescapeJSONError> {
+        let first = self.hex4()?;
+
+        let second = match first {
+            0xd800..=0xdbff => {
+                let mut lookahead = self.input.clone();
+                match (lookahead.next(), lookahead.next()) {
+                    (Some((_, '\\')), Some((_, 'u'))) => {
+                        self.input = lookahead;
+                        Some(self.hex4()?)
+                    }
+                    _ => return Err(self.error(start, "lone surrogate")),
+                }
+            }
+            _ => None,
+        };
+
+        let decoded = match second {
+            Some(second) => char::decode_utf16([first, second]).next(),
+            None => char::decode_utf16([first]).next(),
+        };
+
+        match decoded {
+            Some(Ok(c)) => Ok(c),
+            _ => Err(self.error(start, "lone surrogate")),
+        }
+    }
+
+    fn next_char(&mut self) -> Option<Result<char, UnescapeJSONError>> {
+        if self.finished {
+            return None;
+        }
+
+        let result = match self.input.next() {
+            None => {
+                self.finished = true;
+                return None;
+            }
+            Some((start, '\\')) => match self.input.next() {
+                Some((_, 'u')) => self.unicode_escape(start),
+                Some((_, c)) => match rfc_single_char_unescape(c) {
+                    Some(unescaped) => Ok(unescaped),
+                    None => Err(self.error(start, "invalid escape")),
+                },
+                None => Err(self.error(start, "unterminated escape")),
+            },
+            Some((position, '"')) => Err(self.error(position, "unescaped quotation mark")),
+            Some((position, '\x00'..='\x1f')) => {
+                Err(self.error(position, "unescaped control character"))
+            }
+            Some((_, c)) => Ok(c),
+        };
+
+        Some(result)
+    }
+}
+
+impl Iterator for UnescapeJSONString<'_> {
+    type Item = Result<char, UnescapeJSONError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_char()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.finished {
+            return (0, Some(0));
+        }
+
+        // The shortest escape is a surrogate pair, at 12 chars for 1.
+        let backing_hint = self.input.size_hint();
+        (backing_hint.0.div_ceil(12), backing_hint.1)
+    }
+}
+
+impl FusedIterator for UnescapeJSONString<'_> {}
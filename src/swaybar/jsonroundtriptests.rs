@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::swaybar::json::*;
+
+use EscapePolicy::*;
+
+const POLICIES: [EscapePolicy; 3] = [MinimalEscaping(), AsciiOnly(), Conservative()];
+
+/**
+ * A little xorshift PRNG, so the tests are the same every time and we
+ * don't need a crate for it.
+ */
+#[cfg(test)]
+struct XorShift(u64);
+
+#[cfg(test)]
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u32) -> u32 {
+        u32::try_from(self.next() % u64::from(n)).expect("It's less than a u32.")
+    }
+
+    /**
+     * Pick a char, leaning towards the interesting ones.
+     */
+    fn char(&mut self) -> char {
+        let c = match self.below(8) {
+            0 => self.below(0x20),
+            1 => ['"', '\\', '/', '\u{2028}', '\u{2029}'][self.below(5) as usize].into(),
+            2 => 0x80 + self.below(0x780),
+            3 => 0x800 + self.below(0xf800),
+            4 => 0x10000 + self.below(0x100000),
+            _ => 0x20 + self.below(0x5f),
+        };
+
+        // This could land in the surrogates, which aren't chars.
+        char::from_u32(c).unwrap_or('\u{fffd}')
+    }
+
+    fn string(&mut self) -> String {
+        let len = self.below(24);
+        (0..len).map(|_| self.char()).collect()
+    }
+}
+
+#[test]
+fn check_random_round_trips() {
+    let mut rng = XorShift(0x5eed_0f55_57a7);
+
+    for _ in 0..2000 {
+        let original = rng.string();
+
+        for policy in POLICIES {
+            let escaped = EscapeJSONString::new_from_str(&original, policy).collect::<String>();
+            let unescaped =
+                UnescapeJSONString::new_from_str(&escaped).collect::<Result<String, _>>();
+
+            assert_eq!(
+                unescaped.as_deref(),
+                Ok(original.as_str()),
+                "Escape policy {:?} applied to {:?} gave {:?}, which didn't round trip",
+                policy,
+                original,
+                escaped
+            );
+        }
+    }
+}
+
+#[test]
+fn check_every_bmp_char_round_trips() {
+    let everything = (0..=0xffff).filter_map(char::from_u32).collect::<String>();
+
+    for policy in POLICIES {
+        let escaped = EscapeJSONString::new_from_str(&everything, policy).collect::<String>();
+        let unescaped = UnescapeJSONString::new_from_str(&escaped).collect::<Result<String, _>>();
+
+        assert!(
+            unescaped.as_deref() == Ok(everything.as_str()),
+            "Escape policy {:?} didn't round trip the BMP",
+            policy
+        );
+    }
+}
+
+#[test]
+fn check_random_truncations_fail_cleanly() {
+    let mut rng = XorShift(0xdead_beef_cafe);
+
+    for _ in 0..500 {
+        let original = rng.string();
+        let escaped = EscapeJSONString::new_from_str(&original, AsciiOnly()).collect::<String>();
+
+        // Cutting an escape short should always be an error that points
+        // somewhere inside what we gave it, never a panic.
+        for (cut, _) in escaped.char_indices() {
+            if let Err(e) =
+                UnescapeJSONString::new_from_str(&escaped[..cut]).collect::<Result<String, _>>()
+            {
+                assert!(
+                    e.position <= cut,
+                    "{} is past the end of {:?}",
+                    e,
+                    &escaped[..cut]
+                );
+            }
+        }
+    }
+}
@@ -135,3 +135,131 @@ fn check_mandatory_escapes() {
 
     check_escapes(always_needs_escape_vectors);
 }
+
+#[test]
+fn check_policies_agree_on_mandatory_escapes() {
+    let vectors = ["\x00", "some\x1btext", "\"", "\\"]
+        .into_iter()
+        .map(|input| InputAndExpectedResults {
+            input: input.to_string(),
+            expected_results: [MinimalEscaping(), AsciiOnly(), Conservative()]
+                .into_iter()
+                .map(|policy| {
+                    (
+                        policy,
+                        EscapeJSONString::new_from_str(input, MinimalEscaping()).collect(),
+                    )
+                })
+                .collect(),
+        })
+        .collect();
+
+    check_escapes(vectors);
+}
+
+#[test]
+fn check_ascii_only_escapes() {
+    let vectors = vec![
+        InputAndExpectedResults {
+            input: "plain ascii/text".to_string(),
+            expected_results: HashMap::from([(AsciiOnly(), "plain ascii/text".to_string())]),
+        },
+        InputAndExpectedResults {
+            input: "caf\u{e9}".to_string(),
+            expected_results: HashMap::from([
+                (MinimalEscaping(), "caf\u{e9}".to_string()),
+                (AsciiOnly(), "caf\\u00E9".to_string()),
+            ]),
+        },
+        InputAndExpectedResults {
+            input: "\u{1f600}".to_string(),
+            expected_results: HashMap::from([
+                (MinimalEscaping(), "\u{1f600}".to_string()),
+                (AsciiOnly(), "\\uD83D\\uDE00".to_string()),
+                (Conservative(), "\u{1f600}".to_string()),
+            ]),
+        },
+    ];
+
+    check_escapes(vectors);
+}
+
+#[test]
+fn check_conservative_escapes() {
+    let vectors = vec![
+        InputAndExpectedResults {
+            input: "a/b\n\t".to_string(),
+            expected_results: HashMap::from([
+                (MinimalEscaping(), "a/b\\u000A\\u0009".to_string()),
+                (Conservative(), "a\\/b\\n\\t".to_string()),
+            ]),
+        },
+        InputAndExpectedResults {
+            input: "\x08\x0c\r".to_string(),
+            expected_results: HashMap::from([(Conservative(), "\\b\\f\\r".to_string())]),
+        },
+        InputAndExpectedResults {
+            input: "line\u{2028}para\u{2029}".to_string(),
+            expected_results: HashMap::from([
+                (MinimalEscaping(), "line\u{2028}para\u{2029}".to_string()),
+                (AsciiOnly(), "line\\u2028para\\u2029".to_string()),
+                (Conservative(), "line\\u2028para\\u2029".to_string()),
+            ]),
+        },
+    ];
+
+    check_escapes(vectors);
+}
+
+#[cfg(test)]
+fn unescape(input: &str) -> Result<String, UnescapeJSONError> {
+    UnescapeJSONString::new_from_str(input).collect()
+}
+
+#[cfg(test)]
+fn unescape_error(input: &str) -> (usize, &'static str) {
+    let e = unescape(input).expect_err(input);
+    (e.position, e.what)
+}
+
+#[test]
+fn check_unescapes() {
+    assert_eq!(unescape("").unwrap(), "");
+    assert_eq!(unescape("some text").unwrap(), "some text");
+    assert_eq!(
+        unescape("\\\"\\\\\\/\\b\\f\\n\\r\\t").unwrap(),
+        "\"\\/\x08\x0c\n\r\t"
+    );
+    assert_eq!(unescape("caf\\u00e9 \\u00E9").unwrap(), "caf\u{e9} \u{e9}");
+    assert_eq!(unescape("\\uD83D\\uDE00").unwrap(), "\u{1f600}");
+    assert_eq!(unescape("raw \u{1f600}").unwrap(), "raw \u{1f600}");
+}
+
+#[test]
+fn check_unescape_errors() {
+    assert_eq!(unescape_error("ab\\x"), (2, "invalid escape"));
+    assert_eq!(unescape_error("ab\\"), (2, "unterminated escape"));
+    assert_eq!(unescape_error("\\u12G4"), (4, "expected hex digit"));
+    assert_eq!(unescape_error("\\u12"), (4, "unterminated unicode escape"));
+    assert_eq!(unescape_error("a\"b"), (1, "unescaped quotation mark"));
+    assert_eq!(unescape_error("a\nb"), (1, "unescaped control character"));
+
+    assert_eq!(unescape_error("x\\uD83D"), (1, "lone surrogate"));
+    assert_eq!(unescape_error("x\\uD83Dy"), (1, "lone surrogate"));
+    assert_eq!(unescape_error("x\\uD83D\\n"), (1, "lone surrogate"));
+    assert_eq!(unescape_error("x\\uD83D\\u0041"), (1, "lone surrogate"));
+    assert_eq!(unescape_error("\u{e9}\\uDE00"), (2, "lone surrogate"));
+    assert_eq!(
+        unescape_error("\\uD83D\\uDE0"),
+        (11, "unterminated unicode escape")
+    );
+}
+
+#[test]
+fn check_unescape_stops_after_error() {
+    let mut unescaper = UnescapeJSONString::new_from_str("a\\qb");
+
+    assert_eq!(unescaper.next(), Some(Ok('a')));
+    assert!(matches!(unescaper.next(), Some(Err(_))));
+    assert_eq!(unescaper.next(), None);
+}
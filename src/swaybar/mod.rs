@@ -17,157 +17,157 @@
  * If not, see <https://www.gnu.org/licenses/>.
  */
 
+use nix::sys::signal::Signal as NixSignal;
 use std::error::Error;
-use std::rc::Rc;
-use tokio::io::AsyncWriteExt;
-use tokio::sync::Mutex;
-use tokio::sync::mpsc::Receiver;
+use std::future::poll_fn;
+use std::task::Poll;
+use tokio::signal::unix::{Signal, SignalKind, signal};
+use tokio::sync::mpsc::Sender;
 
-use crate::data::StatusbarChangeCause::{self, BatteryChange, TzChange};
-use crate::data::StatusbarData;
-use crate::io::StatusbarIOContext;
-use crate::time::ShortenedDTD;
+use crate::data::StatusbarChangeCause;
+use crate::render::Renderer;
 
+mod block;
+mod click;
 mod json;
+mod pango;
 
-use json::{EscapeJSONString, EscapePolicy::MinimalEscaping};
+pub use block::{Align, Block, Markup, MinWidth};
+pub use click::read_click_events;
+pub use json::{EscapeJSONString, EscapePolicy};
+pub use pango::{escape_pango, strip_pango};
 
-async fn print_header(io_ctx: &Rc<Mutex<StatusbarIOContext<'_>>>) -> Result<(), Box<dyn Error>> {
-    let header = String::from("{ \"version\": 1 }\n");
+/**
+ * The signals we ask the bar to send when it's hidden and shown. We
+ * don't want the default SIGSTOP and SIGCONT, since those could freeze
+ * us in the middle of writing a status line.
+ */
+const STOP_SIGNAL: NixSignal = NixSignal::SIGUSR1;
+const CONT_SIGNAL: NixSignal = NixSignal::SIGUSR2;
 
-    let output = &mut io_ctx.lock().await.statusbar_output;
+/**
+ * The handlers for our stop and continue signals. These need to be
+ * installed before we print the header, since the default action for
+ * them is to terminate.
+ */
+pub struct StopContSignals {
+    stop: Signal,
+    cont: Signal,
+}
 
-    output.write_all(header.as_bytes()).await?;
+impl StopContSignals {
+    pub fn install() -> Result<Self, Box<dyn Error>> {
+        Ok(StopContSignals {
+            stop: signal(SignalKind::from_raw(STOP_SIGNAL as i32))?,
+            cont: signal(SignalKind::from_raw(CONT_SIGNAL as i32))?,
+        })
+    }
 
-    Ok(())
+    /**
+     * Wait for the next stop or continue signal. Both are read here, so
+     * they come out in the order they got delivered. If both came in
+     * since we last looked, we can't tell which was last, so we go with
+     * continuing: printing while hidden is harmless, but stopping while
+     * shown leaves the bar stale.
+     */
+    async fn recv(&mut self) -> Option<StatusbarChangeCause> {
+        poll_fn(
+            |cx| match (self.stop.poll_recv(cx), self.cont.poll_recv(cx)) {
+                (_, Poll::Ready(Some(()))) => Poll::Ready(Some(StatusbarChangeCause::Cont)),
+                (Poll::Ready(Some(())), _) => Poll::Ready(Some(StatusbarChangeCause::Stop)),
+                (Poll::Ready(None), _) | (_, Poll::Ready(None)) => Poll::Ready(None),
+                (Poll::Pending, Poll::Pending) => Poll::Pending,
+            },
+        )
+        .await
+    }
 }
 
-async fn print_body_begin(
-    io_ctx: &Rc<Mutex<StatusbarIOContext<'_>>>,
+/**
+ * Pass along every stop and continue signal as a change.
+ */
+pub async fn forward_stop_cont(
+    mut signals: StopContSignals,
+    change_q: Sender<StatusbarChangeCause>,
 ) -> Result<(), Box<dyn Error>> {
-    let body_begin = String::from("[\n");
-
-    let output = &mut io_ctx.lock().await.statusbar_output;
-
-    output.write_all(body_begin.as_bytes()).await?;
+    while let Some(cause) = signals.recv().await {
+        change_q.send(cause).await?;
+    }
 
     Ok(())
 }
 
-async fn print_status_line(
-    data: &StatusbarData,
-    io_ctx: &Rc<Mutex<StatusbarIOContext<'_>>>,
-) -> Result<(), Box<dyn Error>> {
-    let line = "  [\n\
-        \x20   {\n\
-        \x20     \"full_text\": \""
-        .chars()
-        .chain(EscapeJSONString::new_from_str(
-            &data.battery().to_string(),
-            MinimalEscaping(),
-        ))
-        .chain(
-            "\",\n\
-        \x20     \"min_width\": \""
-                .chars(),
-        )
-        .chain(EscapeJSONString::new_from_str("000%", MinimalEscaping()))
-        .chain(
-            "\"\n\
-        \x20   },\n\
-        \x20   {\n\
-        \x20     \"full_text\": \""
-                .chars(),
-        )
-        .chain(EscapeJSONString::new_from_str(
-            &data.time().to_string(),
-            MinimalEscaping(),
-        ))
-        .chain(
-            "\",\n\
-        \x20     \"short_text\": \""
-                .chars(),
-        )
-        .chain(EscapeJSONString::new_from_str(
-            &ShortenedDTD(data.time()).to_string(),
-            MinimalEscaping(),
-        ))
-        .chain(
-            "\",\n\
-        \x20     \"min_width\": \""
-                .chars(),
-        )
-        .chain(EscapeJSONString::new_from_str("00:00", MinimalEscaping()))
-        .chain(
-            "\"\n\
-        \x20   }\n\
-        \x20 ],\n"
-                .chars(),
+/**
+ * Talks the i3bar JSON protocol, which swaybar speaks too.
+ */
+pub struct SwaybarRenderer {
+    pub policy: EscapePolicy,
+}
+
+impl Renderer for SwaybarRenderer {
+    fn header(&self) -> String {
+        format!(
+            "{{ \"version\": 1, \"stop_signal\": {}, \"cont_signal\": {}, \"click_events\": true }}\n[\n",
+            STOP_SIGNAL as i32, CONT_SIGNAL as i32
         )
-        .collect::<String>();
+    }
 
-    let output = &mut io_ctx.lock().await.statusbar_output;
+    fn status_line(&self, blocks: &[Block]) -> String {
+        let mut line = status_line_json(blocks, "  ", self.policy);
+        line.push_str(",\n");
 
-    output.write_all(line.as_bytes()).await?;
-    output.flush().await?;
+        line
+    }
 
-    Ok(())
-}
+    /**
+     * Just one status line, without the header or the infinite array
+     * around it.
+     */
+    fn single_status_line(&self, blocks: &[Block]) -> String {
+        let mut line = status_line_json(blocks, "", self.policy);
+        line.push('\n');
 
-async fn print_infinite_body(
-    mut change_q: Receiver<StatusbarChangeCause>,
-    io_ctx: Rc<Mutex<StatusbarIOContext<'_>>>,
-) -> Result<(), Box<dyn Error>> {
-    print_body_begin(&io_ctx).await?;
-
-    let mut data = StatusbarData::new();
-    let mut buf = Vec::with_capacity(4);
-
-    loop {
-        print_status_line(&data, &io_ctx).await?;
-
-        loop {
-            match change_q.recv_many(&mut buf, 128).await {
-                0 => {
-                    // No more, Senders must be shut down. I guess it's time
-                    // to close up.
-                    return Ok(());
-                }
-                _ => {
-                    // Process a bunch of our messages before rerendering.
-                    for msg in buf.drain(..) {
-                        match msg {
-                            TzChange(tz_change) => {
-                                data.update_timezone_maybedata(tz_change);
-                            }
-                            BatteryChange(bat_change) => {
-                                data.update_battery_maybedata(bat_change);
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-            }
-
-            // See if there's more messages that we should process
-            // before printing out the new status line.
-            if change_q.is_empty() {
-                break;
-            }
-        }
+        line
+    }
+
+    fn reads_clicks(&self) -> bool {
+        true
+    }
+
+    fn understands_pango(&self) -> bool {
+        true
     }
 }
 
-pub async fn run_statusbar_updater(
-    change_q: Receiver<StatusbarChangeCause>,
-    io_ctx: Rc<Mutex<StatusbarIOContext<'_>>>,
-) -> Result<(), Box<dyn Error>> {
-    print_header(&io_ctx).await?;
+/**
+ * The blocks as a JSON array, with the brackets indented by `indent`,
+ * and no trailing newline.
+ */
+fn status_line_json(blocks: &[Block], indent: &str, policy: EscapePolicy) -> String {
+    let block_indent = format!("{}  ", indent);
+    let mut line = format!("{}[\n", indent);
 
-    print_infinite_body(change_q, io_ctx).await?;
+    for (i, block) in blocks.iter().enumerate() {
+        if i != 0 {
+            line.push_str(",\n");
+        }
+        block.write_json(&mut line, &block_indent, policy);
+    }
 
-    Ok(())
+    line.push('\n');
+    line.push_str(indent);
+    line.push(']');
+
+    line
 }
 
+#[cfg(test)]
+mod blocktests;
+#[cfg(test)]
+mod clicktests;
+#[cfg(test)]
+mod jsonroundtriptests;
 #[cfg(test)]
 mod jsontests;
+#[cfg(test)]
+mod pangotests;
@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::borrow::Cow;
+
+/**
+ * Escape text for Pango markup, so it shows up as-is instead of being
+ * taken as markup. This happens before JSON escaping.
+ */
+pub fn escape_pango(text: &str) -> Cow<'_, str> {
+    if !text.contains(['&', '<', '>', '\'', '"']) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut escaped = String::with_capacity(text.len() + 8);
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '\'' => escaped.push_str("&apos;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+
+    Cow::Owned(escaped)
+}
+
+/**
+ * The entities `escape_pango` makes, and what they stand for.
+ */
+const ENTITIES: [(&str, char); 5] = [
+    ("&amp;", '&'),
+    ("&lt;", '<'),
+    ("&gt;", '>'),
+    ("&apos;", '\''),
+    ("&quot;", '"'),
+];
+
+/**
+ * Take the markup back out of a format, for outputs that don't do
+ * Pango. The tags get dropped, and entities turn back into what they
+ * stand for.
+ */
+pub fn strip_pango(markup: &str) -> String {
+    let mut stripped = String::with_capacity(markup.len());
+    let mut rest = markup;
+
+    while let Some(start) = rest.find(['<', '&']) {
+        stripped.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if rest.starts_with('<') {
+            match rest.find('>') {
+                Some(end) => rest = &rest[end + 1..],
+                // Not really a tag, so leave it be.
+                None => break,
+            }
+        } else if let Some((entity, c)) =
+            ENTITIES.iter().find(|(entity, _)| rest.starts_with(entity))
+        {
+            stripped.push(*c);
+            rest = &rest[entity.len()..];
+        } else {
+            stripped.push('&');
+            rest = &rest[1..];
+        }
+    }
+
+    stripped.push_str(rest);
+
+    stripped
+}
@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::data::EscapeData;
+use crate::swaybar::pango::*;
+use crate::time::{DateTimeData, FormattedDTD};
+use chrono::TimeZone;
+use chrono_tz::Tz;
+
+#[test]
+fn check_pango_escapes() {
+    assert_eq!(escape_pango("12:34"), "12:34");
+    assert_eq!(
+        escape_pango("<b>Tom & \"Jerry's\"</b>"),
+        "&lt;b&gt;Tom &amp; &quot;Jerry&apos;s&quot;&lt;/b&gt;"
+    );
+}
+
+#[test]
+fn check_pango_clock() {
+    let escape: EscapeData = escape_pango;
+    let time = DateTimeData(Ok(Some(
+        Tz::Europe__London
+            .with_ymd_and_hms(2025, 1, 2, 3, 4, 5)
+            .unwrap(),
+    )));
+
+    // The markup in the format stays, but what we fill in is escaped.
+    assert_eq!(
+        FormattedDTD(&time, "<b>%H:%M</b> %Z <%%>", escape).to_string(),
+        "<b>03:04</b> GMT <%>"
+    );
+
+    let broken = DateTimeData::<Tz>(Err("<oops> & stuff".into()));
+    assert_eq!(
+        FormattedDTD(&broken, "<b>%H:%M</b>", escape).to_string(),
+        "&lt;oops&gt; &amp; stuff"
+    );
+}
+
+#[test]
+fn check_pango_stripping() {
+    assert_eq!(strip_pango("%H:%M"), "%H:%M");
+    assert_eq!(
+        strip_pango("<span color=\"red\"><b>%p%%</b></span> &amp; &lt;%t&gt;"),
+        "%p%% & <%t>"
+    );
+    assert_eq!(
+        strip_pango("Tom & Jerry &copy; 1 < 2"),
+        "Tom & Jerry &copy; 1 < 2"
+    );
+}
@@ -17,20 +17,46 @@
  * If not, see <https://www.gnu.org/licenses/>.
  */
 
+use chrono::format::{Item, StrftimeItems};
 use chrono::{DateTime, TimeZone};
 use chrono_tz::Tz;
 use std::error::Error;
 use std::fmt;
+use std::iter;
+
+use crate::data::EscapeData;
+
+pub mod tick;
+pub mod zone;
 
 pub struct DateTimeData<Tz: TimeZone>(
     pub Result<Option<DateTime<Tz>>, Box<dyn Error + Send + Sync>>,
 );
 
+/**
+ * The formats we use if the config doesn't say otherwise, in
+ * `strftime` syntax.
+ */
+pub const DEFAULT_FORMAT: &str = "%Y-%m-%d %H:%M";
+pub const DEFAULT_SHORT_FORMAT: &str = "%H:%M";
+
+/**
+ * Check that a clock format is something chrono understands, since
+ * formatting with a bad one panics.
+ */
+pub fn check_format(format: &str) -> Result<(), String> {
+    if StrftimeItems::new(format).any(|item| item == Item::Error) {
+        Err(format!("bad clock format \"{}\"", format))
+    } else {
+        Ok(())
+    }
+}
+
 impl fmt::Display for DateTimeData<Tz> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match &self.0 {
             Ok(opt) => match opt {
-                Some(date_time) => write!(f, "{}", date_time.format("%Y-%m-%d %H:%M")),
+                Some(date_time) => write!(f, "{}", date_time.format(DEFAULT_FORMAT)),
                 None => write!(f, "none"),
             },
             Err(e) => write!(f, "{}", e),
@@ -38,68 +64,149 @@ impl fmt::Display for DateTimeData<Tz> {
     }
 }
 
-pub struct ShortenedDTD<Tz: TimeZone>(pub DateTimeData<Tz>);
+/**
+ * The date and time, laid out according to a format, with everything
+ * that didn't come from the format itself escaped.
+ */
+pub struct FormattedDTD<'a, Tz: TimeZone>(pub &'a DateTimeData<Tz>, pub &'a str, pub EscapeData);
 
-impl fmt::Display for ShortenedDTD<Tz> {
+impl fmt::Display for FormattedDTD<'_, Tz> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let escape = self.2;
+
         match &self.0.0 {
-            Ok(Some(date_time)) => write!(f, "{}", date_time.format("%H:%M")),
-            _ => write!(f, "none"),
+            Ok(Some(date_time)) => {
+                for item in StrftimeItems::new(self.1) {
+                    let formatted = date_time
+                        .format_with_items(iter::once(item.clone()))
+                        .to_string();
+
+                    match item {
+                        Item::Literal(_)
+                        | Item::OwnedLiteral(_)
+                        | Item::Space(_)
+                        | Item::OwnedSpace(_) => write!(f, "{}", formatted)?,
+                        _ => write!(f, "{}", escape(&formatted))?,
+                    }
+                }
+
+                Ok(())
+            }
+            Ok(None) => write!(f, "none"),
+            Err(e) => write!(f, "{}", escape(&e.to_string())),
         }
     }
 }
 
 use crate::StatusbarIOContext;
-use chrono::{DurationRound, Local, TimeDelta};
+use chrono::Utc;
 use nix::errno::Errno::{self, EAGAIN, ECANCELED};
 use nix::sys::time::TimeSpec;
 use nix::sys::timerfd::{ClockId, Expiration, TimerFd, TimerFlags, TimerSetTimeFlags};
+use nix::time::{ClockId as TimeClockId, clock_gettime};
 use std::convert::Infallible;
 use std::fs::File;
-use std::io::Read;
+use std::future::poll_fn;
+use std::io::{self, Read};
 use std::os::fd::AsFd;
 use std::rc::Rc;
-use tokio::io::unix::AsyncFd;
+use std::task::Poll;
+use tokio::io::unix::{AsyncFd, AsyncFdReadyGuard};
 use tokio::io::{AsyncWriteExt, Interest, Ready};
 use tokio::sync::Mutex;
+use tokio::sync::mpsc::UnboundedReceiver;
 
-fn get_next_minute_absolute_timespec() -> Result<TimeSpec, Box<dyn Error>> {
-    let start = Local::now();
-    let half_minute = TimeDelta::seconds(30);
-    let minute = TimeDelta::minutes(1);
-    let next_minute = (start + half_minute).duration_round(minute)?;
+use crate::time::tick::{TickGranularity, TickRequest};
 
-    let next_minute_timespec = TimeSpec::new(
-        next_minute.timestamp(),
-        next_minute.timestamp_subsec_nanos().into(),
+/**
+ * Point the timer at the next tick. If ticks come at a steady pace,
+ * the timer keeps going after that, otherwise it has to be set again
+ * after every tick.
+ */
+fn arm_for_next_tick(
+    tfd: &TimerFd,
+    granularity: TickGranularity,
+    timezone: Option<Tz>,
+    listen_flags: TimerSetTimeFlags,
+) -> Result<(), Box<dyn Error>> {
+    let next_tick = granularity.next_tick_in(timezone, &Utc::now())?;
+    let next_tick = TimeSpec::new(
+        next_tick.timestamp(),
+        next_tick.timestamp_subsec_nanos().into(),
     );
 
-    Ok(next_minute_timespec)
+    let wait_for_tick = match granularity.period() {
+        Some(tick_period) => Expiration::IntervalDelayed(next_tick, tick_period),
+        None => Expiration::OneShot(next_tick),
+    };
+
+    tfd.set(wait_for_tick, listen_flags)?;
+
+    Ok(())
+}
+
+/**
+ * How long we've spent suspended since boot. `CLOCK_BOOTTIME` keeps
+ * counting while we're suspended and `CLOCK_MONOTONIC` doesn't, so this
+ * only grows when we sleep. Reading the two clocks isn't atomic, so it
+ * jitters a little.
+ */
+fn time_spent_suspended() -> Result<TimeSpec, Box<dyn Error>> {
+    let boottime = clock_gettime(TimeClockId::CLOCK_BOOTTIME)?;
+    let monotonic = clock_gettime(TimeClockId::CLOCK_MONOTONIC)?;
+
+    Ok(boottime - monotonic)
+}
+
+/**
+ * How much more time spent suspended counts as having been asleep, and
+ * not just jitter.
+ */
+const MIN_SUSPEND: TimeSpec = TimeSpec::new(1, 0);
+
+/**
+ * What woke the ticker up.
+ */
+enum TickWakeup<'a> {
+    Timer(io::Result<AsyncFdReadyGuard<'a, File>>),
+    Request(TickRequest),
 }
 
 pub trait ClockTickCallbacks {
-    async fn changed_minute(&self) -> Result<(), Box<dyn Error>>;
-    async fn minute_maybe_lost(&self) -> Result<(), Box<dyn Error>>;
+    async fn changed_tick(&self) -> Result<(), Box<dyn Error>>;
+    async fn tick_maybe_lost(&self) -> Result<(), Box<dyn Error>>;
     async fn adjustment_happened(&self) -> Result<(), Box<dyn Error>>;
+    /**
+     * We noticed that we were suspended since the last time we woke
+     * up. The timer has already been set again.
+     */
+    async fn resumed_from_suspend(&self) -> Result<(), Box<dyn Error>>;
 }
 
-pub async fn tick_every_minute(
+/**
+ * Call back every time the second, minute, hour, or day changes,
+ * depending on `granularity`, and whenever the clock might have jumped.
+ * A resync sent on `tick_q` (e.g. because we woke up from suspend,
+ * which doesn't always cancel the timer) makes us set the timer again
+ * and treat it like an adjustment. Hours and days go by the zone the
+ * clocks show, once `tick_q` tells us which that is.
+ */
+pub async fn tick_clock(
     io_ctx: Rc<Mutex<StatusbarIOContext<'_>>>,
     clock_tick_callbacks: &impl ClockTickCallbacks,
+    mut granularity: TickGranularity,
+    mut tick_q: UnboundedReceiver<TickRequest>,
 ) -> Result<Infallible, Box<dyn Error>> {
-    let next_tick = get_next_minute_absolute_timespec()?;
-    let tick_period = TimeSpec::new(60, 0);
-
     let listen_flags =
         TimerSetTimeFlags::TFD_TIMER_ABSTIME | TimerSetTimeFlags::TFD_TIMER_CANCEL_ON_SET;
 
-    let wait_for_minute = Expiration::IntervalDelayed(next_tick, tick_period);
-
     let tfd = TimerFd::new(
         ClockId::CLOCK_REALTIME,
         TimerFlags::TFD_NONBLOCK | TimerFlags::TFD_CLOEXEC,
     )?;
-    tfd.set(wait_for_minute, listen_flags)?;
+    // We don't know which zone the clocks show until someone tells us.
+    let mut timezone = None;
+    arm_for_next_tick(&tfd, granularity, timezone, listen_flags)?;
 
     let copy_tfd = File::from(tfd.as_fd().try_clone_to_owned().unwrap());
 
@@ -107,10 +214,101 @@ pub async fn tick_every_minute(
 
     // We just set the timer, so we'll catch changes from now on,
     // but we might have missed one earlier.
-    clock_tick_callbacks.minute_maybe_lost().await?;
+    clock_tick_callbacks.tick_maybe_lost().await?;
+
+    // Once nobody can send us requests anymore, stop checking.
+    let mut requests_open = true;
+
+    // This doesn't need logind, so it works everywhere, but we only
+    // notice once something wakes us up after resuming.
+    let mut last_suspended = time_spent_suspended()?;
 
     loop {
-        match tok_afd.readable().await {
+        let wakeup = poll_fn(|cx| {
+            if requests_open {
+                match tick_q.poll_recv(cx) {
+                    Poll::Ready(Some(request)) => return Poll::Ready(TickWakeup::Request(request)),
+                    Poll::Ready(None) => requests_open = false,
+                    Poll::Pending => {}
+                }
+            }
+
+            tok_afd.poll_read_ready(cx).map(TickWakeup::Timer)
+        })
+        .await;
+
+        // Whatever woke us up, check whether we slept since last time.
+        let suspended = time_spent_suspended()?;
+        let resumed = suspended - last_suspended >= MIN_SUSPEND;
+        if resumed {
+            if cfg!(feature = "debug_sleep") {
+                let output = &mut io_ctx.lock().await.debug_output;
+                output
+                    .write_all("Resumed from suspend\n".as_bytes())
+                    .await?;
+                output.flush().await?;
+            }
+
+            arm_for_next_tick(&tfd, granularity, timezone, listen_flags)?;
+
+            clock_tick_callbacks.resumed_from_suspend().await?;
+        }
+        last_suspended = suspended;
+
+        let readable = match wakeup {
+            TickWakeup::Timer(readable) => {
+                if resumed {
+                    // We just set the timer again and reported the
+                    // jump, so whatever it has to say is stale.
+                    if let Ok(mut guard) = readable {
+                        let _ = tok_afd.get_ref().read(&mut [0; 8]);
+                        guard.clear_ready();
+                    }
+
+                    continue;
+                }
+
+                readable
+            }
+            TickWakeup::Request(TickRequest::SetGranularity(new_granularity)) => {
+                if new_granularity != granularity {
+                    granularity = new_granularity;
+                    arm_for_next_tick(&tfd, granularity, timezone, listen_flags)?;
+                }
+
+                continue;
+            }
+            TickWakeup::Request(TickRequest::SetTimezone(new_timezone)) => {
+                if timezone != Some(new_timezone) {
+                    timezone = Some(new_timezone);
+                    arm_for_next_tick(&tfd, granularity, timezone, listen_flags)?;
+                }
+
+                continue;
+            }
+            TickWakeup::Request(TickRequest::Resync) => {
+                // Noticing the suspend already did what a resync would.
+                if resumed {
+                    continue;
+                }
+
+                if cfg!(feature = "debug_sleep") {
+                    let output = &mut io_ctx.lock().await.debug_output;
+                    output.write_all("Resyncing the timer\n".as_bytes()).await?;
+                    output.flush().await?;
+                }
+
+                // The timer might be stuck on a tick that's long
+                // gone, so set it again before firing the callback.
+                arm_for_next_tick(&tfd, granularity, timezone, listen_flags)?;
+
+                clock_tick_callbacks.adjustment_happened().await?;
+
+                continue;
+            }
+        };
+
+        match readable {
             Ok(mut guard) => {
                 let mut buf: [u8; 8] = [0; 8];
                 let read_res = tok_afd.get_ref().read(&mut buf);
@@ -125,8 +323,13 @@ pub async fn tick_every_minute(
                             output.flush().await?;
                         }
 
-                        // We hit the next minute.
-                        clock_tick_callbacks.changed_minute().await?;
+                        // We hit the next tick. If ticks don't come at a
+                        // steady pace, the timer stopped, so set it again.
+                        if granularity.period().is_none() {
+                            arm_for_next_tick(&tfd, granularity, timezone, listen_flags)?;
+                        }
+
+                        clock_tick_callbacks.changed_tick().await?;
 
                         guard.retain_ready();
                     }
@@ -141,13 +344,9 @@ pub async fn tick_every_minute(
                             }
 
                             // The clock got changed, so the timer got canceled.
-                            // Set it to the next minute, _then_ fire the callback.
+                            // Set it to the next tick, _then_ fire the callback.
 
-                            let next_tick = get_next_minute_absolute_timespec()?;
-                            let wait_for_minute =
-                                Expiration::IntervalDelayed(next_tick, tick_period);
-
-                            tfd.set(wait_for_minute, listen_flags)?;
+                            arm_for_next_tick(&tfd, granularity, timezone, listen_flags)?;
 
                             clock_tick_callbacks.adjustment_happened().await?;
 
@@ -210,12 +409,9 @@ pub async fn tick_every_minute(
                         // TODO: is this unreachable?
 
                         // The clock got changed, so the timer got canceled.
-                        // Set it to the next minute, _then_ fire the callback.
-
-                        let next_tick = get_next_minute_absolute_timespec()?;
-                        let wait_for_minute = Expiration::IntervalDelayed(next_tick, tick_period);
+                        // Set it to the next tick, _then_ fire the callback.
 
-                        tfd.set(wait_for_minute, listen_flags)?;
+                        arm_for_next_tick(&tfd, granularity, timezone, listen_flags)?;
 
                         clock_tick_callbacks.adjustment_happened().await?;
                     }
@@ -228,3 +424,8 @@ pub async fn tick_every_minute(
         }
     }
 }
+
+#[cfg(test)]
+mod ticktests;
+#[cfg(test)]
+mod zonetests;
@@ -0,0 +1,175 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use chrono::format::{Fixed, Item, Numeric, StrftimeItems};
+use chrono::{DateTime, DurationRound, Local, NaiveTime, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+use nix::sys::time::TimeSpec;
+use std::error::Error;
+
+/**
+ * How often the clock needs to change, i.e. the smallest unit of time
+ * that a clock format shows. These are in order, finest first.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TickGranularity {
+    Second,
+    Minute,
+    Hour,
+    Day,
+}
+
+impl TickGranularity {
+    /**
+     * How often a clock format needs to change. Anything we're not sure
+     * about ticks every second, so it can't go stale.
+     */
+    pub fn for_format(format: &str) -> Self {
+        StrftimeItems::new(format)
+            .filter_map(|item| Self::for_item(&item))
+            .min()
+            .unwrap_or(Self::Day)
+    }
+
+    fn for_item(item: &Item) -> Option<Self> {
+        match item {
+            Item::Literal(_) | Item::OwnedLiteral(_) | Item::Space(_) | Item::OwnedSpace(_) => None,
+            Item::Numeric(numeric, _pad) => Some(match numeric {
+                Numeric::Year
+                | Numeric::YearDiv100
+                | Numeric::YearMod100
+                | Numeric::IsoYear
+                | Numeric::IsoYearDiv100
+                | Numeric::IsoYearMod100
+                | Numeric::Quarter
+                | Numeric::Month
+                | Numeric::Day
+                | Numeric::WeekFromSun
+                | Numeric::WeekFromMon
+                | Numeric::IsoWeek
+                | Numeric::NumDaysFromSun
+                | Numeric::WeekdayFromMon
+                | Numeric::Ordinal => Self::Day,
+                Numeric::Hour | Numeric::Hour12 => Self::Hour,
+                Numeric::Minute => Self::Minute,
+                _ => Self::Second,
+            }),
+            Item::Fixed(fixed) => Some(match fixed {
+                Fixed::ShortMonthName
+                | Fixed::LongMonthName
+                | Fixed::ShortWeekdayName
+                | Fixed::LongWeekdayName => Self::Day,
+                Fixed::LowerAmPm | Fixed::UpperAmPm => Self::Hour,
+                // DST changes the zone, and not always on the hour.
+                Fixed::TimezoneName
+                | Fixed::TimezoneOffset
+                | Fixed::TimezoneOffsetColon
+                | Fixed::TimezoneOffsetDoubleColon
+                | Fixed::TimezoneOffsetTripleColon
+                | Fixed::TimezoneOffsetColonZ
+                | Fixed::TimezoneOffsetZ => Self::Minute,
+                _ => Self::Second,
+            }),
+            _ => Some(Self::Second),
+        }
+    }
+
+    /**
+     * How long from one tick to the next, if that's always the same.
+     * Days aren't always 24 hours long, so those get worked out one at a
+     * time.
+     */
+    pub fn period(&self) -> Option<TimeSpec> {
+        match self {
+            Self::Second => Some(TimeSpec::new(1, 0)),
+            Self::Minute => Some(TimeSpec::new(60, 0)),
+            Self::Hour => Some(TimeSpec::new(60 * 60, 0)),
+            Self::Day => None,
+        }
+    }
+
+    /**
+     * When the next tick after `start` is, i.e. the beginning of the
+     * next second, minute, hour, or day, in `start`'s timezone.
+     */
+    pub fn next_tick_after<Tz: TimeZone>(
+        &self,
+        start: &DateTime<Tz>,
+    ) -> Result<DateTime<Tz>, Box<dyn Error>> {
+        let unit = match self {
+            Self::Second => TimeDelta::seconds(1),
+            Self::Minute => TimeDelta::minutes(1),
+            Self::Hour => TimeDelta::hours(1),
+            Self::Day => return next_midnight(start),
+        };
+
+        Ok((start.clone() + unit / 2).duration_round(unit)?)
+    }
+
+    /**
+     * When the next tick after `now` is in `zone`, the one the clocks
+     * show. Until we know which that is, go by the local zone.
+     */
+    pub fn next_tick_in(
+        &self,
+        zone: Option<Tz>,
+        now: &DateTime<Utc>,
+    ) -> Result<DateTime<Utc>, Box<dyn Error>> {
+        Ok(match zone {
+            Some(zone) => self.next_tick_after(&now.with_timezone(&zone))?.to_utc(),
+            None => self.next_tick_after(&now.with_timezone(&Local))?.to_utc(),
+        })
+    }
+}
+
+fn next_midnight<Tz: TimeZone>(start: &DateTime<Tz>) -> Result<DateTime<Tz>, Box<dyn Error>> {
+    let midnight = start
+        .date_naive()
+        .succ_opt()
+        .ok_or("there's no day after this one")?
+        .and_time(NaiveTime::MIN);
+
+    // Some places skip midnight when DST starts, so then the day starts
+    // at the first time that does exist.
+    (0..=24)
+        .map(|hours| midnight + TimeDelta::hours(hours))
+        .find_map(|local| start.timezone().from_local_datetime(&local).earliest())
+        .ok_or_else(|| "can't find the start of the next day".into())
+}
+
+/**
+ * What the ticker can be asked to do while it's running.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TickRequest {
+    /**
+     * Set the timer again, and treat it like an adjustment, e.g.
+     * because we woke up from suspend.
+     */
+    Resync,
+    /**
+     * Tick this often from now on, e.g. because the config changed.
+     */
+    SetGranularity(TickGranularity),
+    /**
+     * The clocks show this zone now, so hours and days start at
+     * different times.
+     */
+    SetTimezone(Tz),
+}
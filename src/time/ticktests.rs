@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::time::tick::*;
+use chrono::{TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+
+#[test]
+fn check_format_granularity() {
+    assert_eq!(
+        TickGranularity::for_format("%H:%M:%S"),
+        TickGranularity::Second
+    );
+    assert_eq!(TickGranularity::for_format("%s"), TickGranularity::Second);
+    assert_eq!(TickGranularity::for_format("%T"), TickGranularity::Second);
+    assert_eq!(
+        TickGranularity::for_format("%Y-%m-%d %H:%M"),
+        TickGranularity::Minute
+    );
+    assert_eq!(
+        TickGranularity::for_format("%a %Z"),
+        TickGranularity::Minute
+    );
+    assert_eq!(TickGranularity::for_format("%I %p"), TickGranularity::Hour);
+    assert_eq!(
+        TickGranularity::for_format("%A, %B %e"),
+        TickGranularity::Day
+    );
+    assert_eq!(TickGranularity::for_format("%F"), TickGranularity::Day);
+    assert_eq!(
+        TickGranularity::for_format("just text %%"),
+        TickGranularity::Day
+    );
+}
+
+#[test]
+fn check_next_ticks() {
+    let start = Tz::Europe__Berlin
+        .with_ymd_and_hms(2025, 3, 14, 15, 9, 26)
+        .unwrap()
+        .with_nanosecond(535_000_000)
+        .unwrap();
+    let next = |granularity: TickGranularity| granularity.next_tick_after(&start).unwrap();
+
+    assert_eq!(
+        next(TickGranularity::Second),
+        Tz::Europe__Berlin
+            .with_ymd_and_hms(2025, 3, 14, 15, 9, 27)
+            .unwrap()
+    );
+    assert_eq!(
+        next(TickGranularity::Minute),
+        Tz::Europe__Berlin
+            .with_ymd_and_hms(2025, 3, 14, 15, 10, 0)
+            .unwrap()
+    );
+    assert_eq!(
+        next(TickGranularity::Hour),
+        Tz::Europe__Berlin
+            .with_ymd_and_hms(2025, 3, 14, 16, 0, 0)
+            .unwrap()
+    );
+    assert_eq!(
+        next(TickGranularity::Day),
+        Tz::Europe__Berlin
+            .with_ymd_and_hms(2025, 3, 15, 0, 0, 0)
+            .unwrap()
+    );
+}
+
+#[test]
+fn check_ticks_on_the_boundary_move_on() {
+    let start = Tz::UTC.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+
+    assert_eq!(
+        TickGranularity::Minute.next_tick_after(&start).unwrap(),
+        Tz::UTC.with_ymd_and_hms(2025, 1, 1, 0, 1, 0).unwrap()
+    );
+    assert_eq!(
+        TickGranularity::Day.next_tick_after(&start).unwrap(),
+        Tz::UTC.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap()
+    );
+}
+
+#[test]
+fn check_odd_offsets_and_skipped_midnights() {
+    // Hours start on the half hour in UTC for India.
+    let kolkata = Tz::Asia__Kolkata
+        .with_ymd_and_hms(2025, 6, 1, 9, 45, 0)
+        .unwrap();
+    assert_eq!(
+        TickGranularity::Hour.next_tick_after(&kolkata).unwrap(),
+        Tz::Asia__Kolkata
+            .with_ymd_and_hms(2025, 6, 1, 10, 0, 0)
+            .unwrap()
+    );
+
+    // Santiago skips from midnight to 01:00 when DST starts.
+    let santiago = Tz::America__Santiago
+        .with_ymd_and_hms(2025, 9, 6, 22, 0, 0)
+        .unwrap();
+    assert_eq!(
+        TickGranularity::Day.next_tick_after(&santiago).unwrap(),
+        Tz::America__Santiago
+            .with_ymd_and_hms(2025, 9, 7, 1, 0, 0)
+            .unwrap()
+    );
+}
+
+#[test]
+fn check_ticks_follow_shown_zone() {
+    // The same moment is a different time of day in different zones.
+    let now = Utc.with_ymd_and_hms(2025, 6, 1, 4, 15, 0).unwrap();
+    let next =
+        |granularity: TickGranularity, zone| granularity.next_tick_in(Some(zone), &now).unwrap();
+
+    assert_eq!(
+        next(TickGranularity::Hour, Tz::UTC),
+        Utc.with_ymd_and_hms(2025, 6, 1, 5, 0, 0).unwrap()
+    );
+    assert_eq!(
+        next(TickGranularity::Hour, Tz::Asia__Kolkata),
+        Utc.with_ymd_and_hms(2025, 6, 1, 4, 30, 0).unwrap()
+    );
+    assert_eq!(
+        next(TickGranularity::Day, Tz::UTC),
+        Utc.with_ymd_and_hms(2025, 6, 2, 0, 0, 0).unwrap()
+    );
+    assert_eq!(
+        next(TickGranularity::Day, Tz::Asia__Kolkata),
+        Utc.with_ymd_and_hms(2025, 6, 1, 18, 30, 0).unwrap()
+    );
+    assert_eq!(
+        next(TickGranularity::Minute, Tz::Asia__Kolkata),
+        Utc.with_ymd_and_hms(2025, 6, 1, 4, 16, 0).unwrap()
+    );
+}
@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use chrono_tz::Tz;
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+const LOCALTIME: &str = "/etc/localtime";
+const TIMEZONE: &str = "/etc/timezone";
+
+/**
+ * We couldn't figure out which zone a file refers to.
+ */
+#[derive(Debug)]
+pub struct UnknownZoneFile(pub PathBuf);
+
+impl fmt::Display for UnknownZoneFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown zone {}", self.0.display())
+    }
+}
+
+impl Error for UnknownZoneFile {}
+
+/**
+ * Where to get the local timezone from, when we aren't asking
+ * timedated about it.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LocalTimezone {
+    /**
+     * `TZ` names a zone, so it can't change under us.
+     */
+    Named(String),
+    /**
+     * `TZ` names a zone file, usually a symlink into the tz database.
+     */
+    File(PathBuf),
+    /**
+     * The system default, from `/etc/localtime` or `/etc/timezone`.
+     */
+    System,
+}
+
+impl LocalTimezone {
+    /**
+     * The timezone from the environment, if `TZ` is set. Like libc, a
+     * leading ':' is ignored, and an empty `TZ` means UTC.
+     */
+    pub fn from_env() -> Option<Self> {
+        let tz = env::var("TZ").ok()?;
+        let tz = tz.strip_prefix(':').unwrap_or(&tz);
+
+        Some(if tz.is_empty() {
+            Self::Named("UTC".to_string())
+        } else if tz.starts_with('/') {
+            Self::File(tz.into())
+        } else {
+            Self::Named(tz.to_string())
+        })
+    }
+
+    /**
+     * Figure out which zone this is right now.
+     */
+    pub fn resolve(&self) -> Result<Tz, Box<dyn Error + Send + Sync>> {
+        match self {
+            Self::Named(name) => Ok(name.parse::<Tz>()?),
+            Self::File(path) => tz_from_zone_file(path),
+            Self::System => tz_from_zone_file(Path::new(LOCALTIME)).or_else(|e| {
+                // Some systems copy the zone file instead of linking it,
+                // but Debian and friends also write the name down.
+                match fs::read_to_string(TIMEZONE) {
+                    Ok(name) => Ok(name.trim().parse::<Tz>()?),
+                    Err(_) => Err(e),
+                }
+            }),
+        }
+    }
+
+    /**
+     * Which files to watch for changes to this zone, as a directory and
+     * the names in it.
+     */
+    pub fn watched_files(&self) -> Option<(PathBuf, Vec<String>)> {
+        match self {
+            Self::Named(_) => None,
+            Self::File(path) => {
+                let dir = path.parent()?;
+                let name = path.file_name()?.to_str()?;
+                Some((dir.into(), vec![name.to_string()]))
+            }
+            Self::System => Some((
+                "/etc".into(),
+                vec!["localtime".to_string(), "timezone".to_string()],
+            )),
+        }
+    }
+}
+
+/**
+ * Figure out the zone name from a path into the tz database, e.g.
+ * `/usr/share/zoneinfo/Europe/Berlin`. The "posix" and "right" trees
+ * have the same zones, just with different leap second handling.
+ */
+pub fn tz_from_zoneinfo_path(path: &Path) -> Option<Tz> {
+    let components: Vec<_> = path.components().collect();
+    let after_zoneinfo = components
+        .iter()
+        .rposition(|component| component.as_os_str() == "zoneinfo")?
+        + 1;
+
+    let mut zone = components[after_zoneinfo..]
+        .iter()
+        .map(|component| match component {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    if let Some(&("posix" | "right")) = zone.first() {
+        zone.remove(0);
+    }
+
+    zone.join("/").parse::<Tz>().ok()
+}
+
+/**
+ * Figure out the zone from a zone file, by following it if it's a
+ * symlink into the tz database.
+ */
+fn tz_from_zone_file(path: &Path) -> Result<Tz, Box<dyn Error + Send + Sync>> {
+    let target = match fs::read_link(path) {
+        Ok(target) => target,
+        Err(_) => path.into(),
+    };
+
+    tz_from_zoneinfo_path(&target)
+        .or_else(|| tz_from_zoneinfo_path(&fs::canonicalize(path).ok()?))
+        .ok_or_else(|| UnknownZoneFile(path.into()).into())
+}
@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/* Silly Simple Status(bar) widget
+ * Copyright (C) 2025 Douglas Storm Hill
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as
+ * published by the Free Software Foundation, version 3 of the License.
+ *
+ * This program is distributed in the hope that it will be useful, but
+ * WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+ * Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public
+ * License along with this program.
+ * If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::time::zone::*;
+use chrono_tz::Tz;
+use std::path::Path;
+
+#[test]
+fn check_zoneinfo_paths() {
+    assert_eq!(
+        tz_from_zoneinfo_path(Path::new("/usr/share/zoneinfo/Europe/Berlin")),
+        Some(Tz::Europe__Berlin)
+    );
+    assert_eq!(
+        tz_from_zoneinfo_path(Path::new("../usr/share/zoneinfo/America/Argentina/Salta")),
+        Some(Tz::America__Argentina__Salta)
+    );
+    assert_eq!(
+        tz_from_zoneinfo_path(Path::new("/usr/share/zoneinfo/posix/UTC")),
+        Some(Tz::UTC)
+    );
+    assert_eq!(
+        tz_from_zoneinfo_path(Path::new("/usr/share/zoneinfo/Not/A_Zone")),
+        None
+    );
+    assert_eq!(tz_from_zoneinfo_path(Path::new("/etc/localtime")), None);
+}